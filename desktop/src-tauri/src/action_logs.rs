@@ -1,4 +1,7 @@
-use crate::AppHandle;
+use crate::{
+    commands::cli_log::{self, LogLine},
+    AppHandle,
+};
 use anyhow::Context;
 use log::info;
 use std::{
@@ -70,6 +73,17 @@ pub fn get_action_logs(
     Ok(lines)
 }
 
+/// Same as `get_action_logs`, but decodes the lines the CLI logged as JSON.
+#[tauri::command]
+pub fn get_action_log_events(
+    app_handle: AppHandle,
+    action_id: String,
+) -> Result<Vec<LogLine>, ActionLogError> {
+    let lines = get_action_logs(app_handle, action_id)?;
+
+    Ok(lines.iter().map(|line| cli_log::decode(line)).collect())
+}
+
 #[tauri::command]
 pub fn sync_action_logs(app_handle: AppHandle, actions: Vec<String>) -> Result<(), ActionLogError> {
     let now = SystemTime::now();
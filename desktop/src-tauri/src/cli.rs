@@ -0,0 +1,153 @@
+use crate::{
+    commands::{
+        exec_blocking,
+        runner::{set_cli_path_override, set_environment_profile as apply_environment_profile},
+        version::VersionCommand,
+        DevpodCommandConfig, DevpodCommandError,
+    },
+    refresher,
+    settings::Settings,
+    ui_messages::{ShowToastMsg, ToastStatus},
+    AppHandle, AppState, UiMessage,
+};
+use log::{error, info, warn};
+use semver::Version;
+use std::{collections::HashMap, path::PathBuf, thread};
+use tauri::Manager;
+
+/// `setup` points the commands at the CLI and environment configured in the settings and checks the CLI version in the background.
+pub fn setup(app_handle: &AppHandle) {
+    apply_cli_path(Settings::cli_path(app_handle));
+    if let Err(err) = apply_environment_profile(Settings::environment_profile(app_handle)) {
+        error!("Ignoring invalid environment profile: {}", err);
+    }
+
+    let app_handle = app_handle.clone();
+    thread::spawn(move || check_version(&app_handle));
+}
+
+fn apply_cli_path(cli_path: Option<String>) {
+    if let Some(cli_path) = &cli_path {
+        info!("Using CLI at {}", cli_path);
+    }
+
+    set_cli_path_override(cli_path.map(PathBuf::from));
+}
+
+/// `check_version` compares the CLI version with the app version and warns the user if they don't match.
+fn check_version(app_handle: &AppHandle) {
+    let app_version = &app_handle.package_info().version;
+
+    let toast = match VersionCommand::new().exec() {
+        Ok(cli_version) => match parse_version(&cli_version) {
+            Some(cli_version) if !is_compatible(app_version, &cli_version) => ShowToastMsg::new(
+                "Incompatible CLI version".to_string(),
+                format!(
+                    "DevPod {} might not work with CLI version {}",
+                    app_version, cli_version
+                ),
+                ToastStatus::Warning,
+            ),
+            Some(..) => return,
+            None => {
+                warn!("Unable to parse CLI version {:?}", cli_version);
+                return;
+            }
+        },
+        Err(err) => {
+            error!("Failed to get CLI version: {}", err);
+            ShowToastMsg::new(
+                "Unable to run the DevPod CLI".to_string(),
+                err.to_string(),
+                ToastStatus::Error,
+            )
+        }
+    };
+
+    tauri::async_runtime::block_on(async {
+        let state = app_handle.state::<AppState>();
+        if let Err(err) = state.ui_messages.send(UiMessage::ShowToast(toast)).await {
+            error!(
+                "Failed to broadcast show toast message: {:?}, {}",
+                err.0, err
+            );
+        }
+    });
+}
+
+fn parse_version(version: &str) -> Option<Version> {
+    Version::parse(version.trim().trim_start_matches('v')).ok()
+}
+
+/// Versions are compatible if their major and minor versions match, or if either of them is a development build.
+fn is_compatible(app_version: &Version, cli_version: &Version) -> bool {
+    let dev_version = Version::new(0, 0, 0);
+    if *app_version == dev_version || *cli_version == dev_version {
+        return true;
+    }
+
+    app_version.major == cli_version.major && app_version.minor == cli_version.minor
+}
+
+/// Sets the CLI to use instead of the bundled sidecar, `None` goes back to the sidecar.
+#[tauri::command]
+pub async fn set_cli_path(
+    app_handle: AppHandle,
+    cli_path: Option<String>,
+) -> Result<String, DevpodCommandError> {
+    let cli_path = cli_path.filter(|cli_path| !cli_path.trim().is_empty());
+    if let Some(cli_path) = &cli_path {
+        if !PathBuf::from(cli_path).is_file() {
+            return Err(DevpodCommandError::InvalidArgument(
+                "cliPath",
+                format!("{} is not a file", cli_path),
+            ));
+        }
+    }
+
+    if let Err(err) = Settings::set_cli_path(&app_handle, cli_path.as_deref()) {
+        error!("Failed to persist CLI path: {}", err);
+    }
+    apply_cli_path(cli_path);
+
+    // returns the version so the UI can show which CLI is in use
+    exec_blocking(VersionCommand::new()).await
+}
+
+/// Replaces the environment variables passed to every command.
+#[tauri::command]
+pub fn set_environment_profile(
+    app_handle: AppHandle,
+    env: HashMap<String, String>,
+) -> Result<(), DevpodCommandError> {
+    apply_environment_profile(env.clone())?;
+    // DEVPOD_HOME might have moved, the CLI state has to be watched and read from there
+    refresher::environment_changed();
+    if let Err(err) = Settings::set_environment_profile(&app_handle, &env) {
+        error!("Failed to persist environment profile: {}", err);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_cli_versions() {
+        assert_eq!(parse_version("v0.1.9\n"), Some(Version::new(0, 1, 9)));
+        assert_eq!(parse_version("0.2.0"), Some(Version::new(0, 2, 0)));
+        assert_eq!(parse_version("dev"), None);
+    }
+
+    #[test]
+    fn should_check_compatibility() {
+        let app_version = Version::new(0, 2, 1);
+
+        assert!(is_compatible(&app_version, &Version::new(0, 2, 0)));
+        assert!(!is_compatible(&app_version, &Version::new(0, 1, 9)));
+        assert!(!is_compatible(&app_version, &Version::new(1, 2, 1)));
+        assert!(is_compatible(&app_version, &Version::new(0, 0, 0)));
+    }
+}
@@ -1,7 +1,36 @@
 mod config;
 pub mod constants;
-pub use config::{DevpodCommandConfig, DevpodCommandError};
+pub use config::{exec_blocking, DevpodCommandConfig, DevpodCommandError};
 pub use constants::DEVPOD_BINARY_NAME;
 
+pub mod add_provider;
+pub mod build_workspace;
+pub mod cli_log;
+pub mod create_context;
+pub mod create_machine;
+pub mod delete_context;
+pub mod delete_machine;
 pub mod delete_provider;
+pub mod delete_workspace;
+pub mod ide_options;
+pub mod list_contexts;
+pub mod list_ides;
+pub mod list_machines;
+pub mod list_providers;
 pub mod list_workspaces;
+pub mod machine_status;
+pub mod provider_options;
+pub mod runner;
+pub mod set_context_options;
+pub mod set_provider_options;
+pub mod start_machine;
+pub mod stop_machine;
+pub mod stop_workspace;
+pub mod stream;
+pub mod up_workspace;
+pub mod update_provider;
+pub mod use_context;
+pub mod use_ide;
+pub mod use_provider;
+pub mod version;
+pub mod workspace_status;
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{
+        serialize_options, validate_arg, validate_env, CommandConfig, DevpodCommandConfig,
+        DevpodCommandError,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_ADD, DEVPOD_COMMAND_PROVIDER, LOG_OUTPUT_JSON_ARG,
+        NAME_FLAG, NO_USE_ARG, OPTION_FLAG, USE_ARG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct AddProviderArgs {
+    /// Github repository, URL or path to the provider
+    pub source: String,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+    /// Activate the provider after adding it
+    #[serde(default)]
+    pub use_provider: bool,
+    /// Passed to the CLI while it fetches and initializes the provider
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+pub struct AddProviderCommand {
+    args: AddProviderArgs,
+    options: Vec<String>,
+}
+impl AddProviderCommand {
+    pub fn new(args: AddProviderArgs) -> Result<Self, DevpodCommandError> {
+        validate_env(&args.env)?;
+        validate_arg("source", &args.source)?;
+        if let Some(name) = &args.name {
+            validate_arg("name", name)?;
+        }
+        let options = serialize_options(&args.options)?;
+
+        Ok(AddProviderCommand { args, options })
+    }
+}
+impl DevpodCommandConfig<()> for AddProviderCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![
+            DEVPOD_COMMAND_PROVIDER,
+            DEVPOD_COMMAND_ADD,
+            &self.args.source,
+        ];
+        if let Some(name) = &self.args.name {
+            args.extend([NAME_FLAG, name]);
+        }
+        for option in &self.options {
+            args.extend([OPTION_FLAG, option]);
+        }
+        args.push(if self.args.use_provider {
+            USE_ARG
+        } else {
+            NO_USE_ARG
+        });
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            env: self.args.env.clone(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{validate_arg, validate_env, CommandConfig, DevpodCommandConfig, DevpodCommandError},
+    constants::{
+        DEBUG_ARG, DEVCONTAINER_PATH_FLAG, DEVPOD_BINARY_NAME, DEVPOD_COMMAND_BUILD,
+        LOG_OUTPUT_JSON_ARG, PLATFORM_FLAG, PROVIDER_FLAG, REPOSITORY_FLAG, SKIP_PUSH_ARG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct BuildWorkspaceArgs {
+    /// Workspace ID or source to build
+    pub source: String,
+    pub provider_id: Option<String>,
+    /// The repository to push the prebuild to
+    pub repository: Option<String>,
+    pub devcontainer_path: Option<String>,
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    #[serde(default)]
+    pub skip_push: bool,
+    #[serde(default)]
+    pub debug: bool,
+    /// Passed to the build, e.g. credentials for the prebuild repository
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+pub struct BuildWorkspaceCommand {
+    args: BuildWorkspaceArgs,
+    platforms: Option<String>,
+}
+impl BuildWorkspaceCommand {
+    pub fn new(args: BuildWorkspaceArgs) -> Result<Self, DevpodCommandError> {
+        validate_env(&args.env)?;
+        validate_arg("source", &args.source)?;
+        if let Some(provider_id) = &args.provider_id {
+            validate_arg("providerId", provider_id)?;
+        }
+        if let Some(repository) = &args.repository {
+            validate_arg("repository", repository)?;
+        }
+        if let Some(devcontainer_path) = &args.devcontainer_path {
+            validate_arg("devcontainerPath", devcontainer_path)?;
+        }
+        for platform in &args.platforms {
+            validate_arg("platforms", platform)?;
+        }
+
+        let platforms = if args.platforms.is_empty() {
+            None
+        } else {
+            Some(args.platforms.join(","))
+        };
+
+        Ok(BuildWorkspaceCommand { args, platforms })
+    }
+}
+impl DevpodCommandConfig<()> for BuildWorkspaceCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_BUILD, &self.args.source];
+        if let Some(provider_id) = &self.args.provider_id {
+            args.extend([PROVIDER_FLAG, provider_id]);
+        }
+        if let Some(repository) = &self.args.repository {
+            args.extend([REPOSITORY_FLAG, repository]);
+        }
+        if let Some(devcontainer_path) = &self.args.devcontainer_path {
+            args.extend([DEVCONTAINER_PATH_FLAG, devcontainer_path]);
+        }
+        if let Some(platforms) = &self.platforms {
+            args.extend([PLATFORM_FLAG, platforms]);
+        }
+        if self.args.skip_push {
+            args.push(SKIP_PUSH_ARG);
+        }
+        if self.args.debug {
+            args.push(DEBUG_ARG);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            env: self.args.env.clone(),
+        }
+    }
+}
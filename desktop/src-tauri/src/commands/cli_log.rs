@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+const LEVEL_KEY: &str = "level";
+const MESSAGE_KEY: &str = "message";
+const TIME_KEY: &str = "time";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Done,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    fn parse(level: &str) -> Option<Self> {
+        match level.to_lowercase().as_str() {
+            "trace" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "info" => Some(Self::Info),
+            "done" => Some(Self::Done),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" => Some(Self::Error),
+            "fatal" | "panic" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        *self >= Self::Error
+    }
+}
+
+/// `LogEvent` is a single line the CLI logged with `--log-output=json`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct LogEvent {
+    pub level: LogLevel,
+    pub message: String,
+    pub time: Option<DateTime<Utc>>,
+    /// Everything else the CLI attached to the line
+    #[ts(type = "Record<string, unknown>")]
+    pub fields: HashMap<String, Value>,
+}
+
+/// `LogLine` is a line of CLI output, either a structured log event or plain text, e.g. from a command that doesn't support JSON logs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+#[ts(export)]
+pub enum LogLine {
+    Event(LogEvent),
+    Text(String),
+}
+
+impl LogLine {
+    pub fn event(&self) -> Option<&LogEvent> {
+        match self {
+            Self::Event(event) => Some(event),
+            Self::Text(..) => None,
+        }
+    }
+}
+
+/// `parse_event` decodes a JSON log line, returns `None` if `line` isn't one.
+pub fn parse_event(line: &str) -> Option<LogEvent> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+
+    let mut fields: Map<String, Value> = serde_json::from_str(line).ok()?;
+    let level = LogLevel::parse(fields.get(LEVEL_KEY)?.as_str()?)?;
+    let message = match fields.remove(MESSAGE_KEY)? {
+        Value::String(message) => message,
+        other => other.to_string(),
+    };
+    fields.remove(LEVEL_KEY);
+    let time = fields
+        .remove(TIME_KEY)
+        .and_then(|time| time.as_str().map(String::from))
+        .and_then(|time| DateTime::parse_from_rfc3339(&time).ok())
+        .map(|time| time.with_timezone(&Utc));
+
+    Some(LogEvent {
+        level,
+        message,
+        time,
+        fields: fields.into_iter().collect(),
+    })
+}
+
+/// `decode` turns a line of CLI output into a `LogLine`, falling back to plain text.
+pub fn decode(line: &str) -> LogLine {
+    match parse_event(line) {
+        Some(event) => LogLine::Event(event),
+        None => LogLine::Text(line.to_string()),
+    }
+}
+
+/// `last_error` returns the message of the last `error` or `fatal` event in `output`.
+pub fn last_error(output: &str) -> Option<String> {
+    output
+        .lines()
+        .filter_map(parse_event)
+        .filter(|event| event.level.is_error())
+        .last()
+        .map(|event| event.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_log_events() {
+        let event = parse_event(
+            r#"{"time":"2023-06-01T10:00:00Z","message":"Creating workspace","level":"info","workspace":"my-workspace"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.level, LogLevel::Info);
+        assert_eq!(event.message, "Creating workspace");
+        assert_eq!(
+            event.time,
+            Some(
+                DateTime::parse_from_rfc3339("2023-06-01T10:00:00Z")
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )
+        );
+        assert_eq!(
+            event.fields,
+            HashMap::from([("workspace".to_string(), Value::from("my-workspace"))])
+        );
+    }
+
+    #[test]
+    fn should_fall_back_to_text() {
+        assert_eq!(decode("plain output"), LogLine::Text("plain output".into()));
+        assert_eq!(
+            decode(r#"{"message":"no level"}"#),
+            LogLine::Text(r#"{"message":"no level"}"#.into())
+        );
+        assert!(decode(r#"{"message":"done","level":"done"}"#)
+            .event()
+            .is_some());
+    }
+
+    #[test]
+    fn should_extract_last_error() {
+        let output = [
+            r#"{"time":"2023-06-01T10:00:00Z","message":"Creating workspace","level":"info"}"#,
+            "not json",
+            r#"{"time":"2023-06-01T10:00:01Z","message":"first error","level":"error"}"#,
+            r#"{"time":"2023-06-01T10:00:02Z","message":"provider not found","level":"fatal"}"#,
+        ]
+        .join("\n");
+
+        assert_eq!(last_error(&output), Some("provider not found".to_string()));
+        assert_eq!(last_error("plain output"), None);
+    }
+}
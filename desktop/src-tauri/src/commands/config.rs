@@ -1,15 +1,44 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime},
+};
 
-use tauri::api::process::Command;
+use log::{error, warn};
+use serde::{de::DeserializeOwned, Serialize};
+use tauri::{api::process::Command, Manager};
 use thiserror::Error;
+use tokio::sync::oneshot;
+use ts_rs::TS;
 
-use crate::commands::constants::DEVPOD_BINARY_NAME;
+use crate::{
+    commands::constants::DEVPOD_BINARY_NAME,
+    invocation_history::{self, redact_argv, Caller, InvocationRecord},
+    AppHandle, AppState,
+};
 
-use super::constants::DEVPOD_UI_ENV_VAR;
+use super::{
+    cli_log::last_error,
+    runner::{new_cli_command, CommandRunner, RunOutput, SidecarRunner},
+    stream::{
+        new_invocation_id, CommandHandle, CommandOutput, CommandOutputEvent, COMMAND_OUTPUT_EVENT,
+    },
+};
 
 pub struct CommandConfig<'a> {
     pub(crate) binary_name: &'static str,
     pub(crate) args: Vec<&'a str>,
+    /// Environment variables for this invocation only, they take precedence over the environment profile
+    pub(crate) env: HashMap<String, String>,
+}
+
+impl Default for CommandConfig<'_> {
+    fn default() -> Self {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![],
+            env: HashMap::new(),
+        }
+    }
 }
 
 impl<'a> CommandConfig<'_> {
@@ -20,6 +49,81 @@ impl<'a> CommandConfig<'_> {
     pub fn args(&self) -> &Vec<&str> {
         &self.args
     }
+
+    pub fn env(&self) -> &HashMap<String, String> {
+        &self.env
+    }
+
+    /// The full command line, starting with the binary name.
+    pub fn argv(&self) -> Vec<String> {
+        std::iter::once(self.binary_name)
+            .chain(self.args.iter().copied())
+            .map(String::from)
+            .collect()
+    }
+
+    /// `redacted_argv` is the full command line with secrets replaced, it's what errors carry.
+    pub fn redacted_argv(&self) -> Vec<String> {
+        redact_argv(&self.argv())
+    }
+}
+
+/// Number of stderr lines kept in a `CommandFailure`.
+const STDERR_TAIL_LINES: usize = 20;
+
+/// `CommandFailure` describes a command that ran but exited with a non-zero code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CommandFailure {
+    pub argv: Vec<String>,
+    pub exit_code: Option<i32>,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    pub stderr_tail: Vec<String>,
+    /// The last error the CLI logged, if it ran with `--log-output=json`
+    pub cli_message: Option<String>,
+}
+
+impl CommandFailure {
+    fn new(argv: Vec<String>, output: &RunOutput, started: Instant) -> Self {
+        let stderr_lines: Vec<&str> = output.stderr.lines().collect();
+        let stderr_tail = stderr_lines[stderr_lines.len().saturating_sub(STDERR_TAIL_LINES)..]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        let cli_message = last_error(&output.stderr).or_else(|| last_error(&output.stdout));
+
+        CommandFailure {
+            argv,
+            exit_code: output.exit_code,
+            duration_ms: started.elapsed().as_millis() as u64,
+            stderr_tail,
+            cli_message,
+        }
+    }
+
+    /// The most useful single line describing why the command failed.
+    fn reason(&self) -> Option<&str> {
+        self.cli_message
+            .as_deref()
+            .or_else(|| self.stderr_tail.last().map(String::as_str))
+    }
+}
+
+impl std::fmt::Display for CommandFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "command `{}` exited with ", self.argv.join(" "))?;
+        match self.exit_code {
+            Some(code) => write!(f, "code {}", code)?,
+            None => write!(f, "no exit code")?,
+        }
+        if let Some(reason) = self.reason() {
+            write!(f, ": {}", reason)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Error, Debug)]
@@ -28,40 +132,412 @@ pub enum DevpodCommandError {
     Parse(#[from] serde_json::Error),
     #[error("unable to find sidecar binary")]
     Sidecar,
-    #[error("unable to collect output from command")]
-    Output,
+    #[error("unable to collect output from command `{}`", .argv.join(" "))]
+    Output {
+        argv: Vec<String>,
+        #[source]
+        source: tauri::api::Error,
+    },
     #[error("command failed")]
     Failed(#[from] tauri::api::Error),
-    #[error("command exited with non-zero code")]
-    Exit,
+    #[error("{0}")]
+    Exit(Box<CommandFailure>),
+    #[error("command {0} is not running")]
+    NotRunning(String),
+    #[error("invalid argument {0}: {1}")]
+    InvalidArgument(&'static str, String),
+    #[error("unable to join command task")]
+    Join(#[source] tauri::Error),
+    #[error("workspace {0} is busy: {1}")]
+    Busy(String, String),
+    #[error("command `{}` timed out after {}ms", .argv.join(" "), .timeout_ms)]
+    Timeout { argv: Vec<String>, timeout_ms: u64 },
+    #[error("giving up after {attempts} attempts: {last}")]
+    RetriesExhausted {
+        attempts: u32,
+        last: Box<DevpodCommandError>,
+    },
 }
+
+impl DevpodCommandError {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Parse(..) => "parse",
+            Self::Sidecar => "sidecar",
+            Self::Output { .. } => "output",
+            Self::Failed(..) => "failed",
+            Self::Exit(..) => "exit",
+            Self::NotRunning(..) => "notRunning",
+            Self::InvalidArgument(..) => "invalidArgument",
+            Self::Join(..) => "join",
+            Self::Busy(..) => "busy",
+            Self::Timeout { .. } => "timeout",
+            Self::RetriesExhausted { .. } => "retriesExhausted",
+        }
+    }
+
+    fn failure(&self) -> Option<&CommandFailure> {
+        match self {
+            Self::Exit(failure) => Some(failure),
+            Self::RetriesExhausted { last, .. } => last.failure(),
+            _ => None,
+        }
+    }
+
+    /// Only failures of the command itself are worth retrying, everything else fails the same way again.
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Exit(..) | Self::Timeout { .. } | Self::Output { .. }
+        )
+    }
+}
+
+/// `RetryPolicy` retries a command with exponential backoff.
+/// Only idempotent commands, like `list` or `status`, should have one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub const READ: RetryPolicy = RetryPolicy {
+        attempts: 3,
+        initial_backoff: Duration::from_millis(250),
+        max_backoff: Duration::from_secs(2),
+    };
+}
+
+/// Default timeout for commands that only read local state.
+pub(super) const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// Default timeout for commands that need to reach the provider.
+pub(super) const STATUS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// `CommandErrorPayload` is what the UI receives when a command fails.
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CommandErrorPayload {
+    kind: String,
+    message: String,
+    failure: Option<CommandFailure>,
+}
+
 impl serde::Serialize for DevpodCommandError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        CommandErrorPayload {
+            kind: self.kind().to_string(),
+            message: self.to_string(),
+            failure: self.failure().cloned(),
+        }
+        .serialize(serializer)
     }
 }
-pub trait DevpodCommandConfig<T> {
-    fn config(&self) -> CommandConfig {
-        CommandConfig {
-            binary_name: DEVPOD_BINARY_NAME,
-            args: vec![],
+
+/// `validate_arg` makes sure a user provided value can safely be passed as a positional argument or flag value.
+pub(super) fn validate_arg(name: &'static str, value: &str) -> Result<(), DevpodCommandError> {
+    if value.trim().is_empty() {
+        return Err(DevpodCommandError::InvalidArgument(
+            name,
+            "must not be empty".to_string(),
+        ));
+    }
+    if value.starts_with('-') {
+        return Err(DevpodCommandError::InvalidArgument(
+            name,
+            format!("must not start with '-', got {}", value),
+        ));
+    }
+
+    Ok(())
+}
+
+/// `serialize_options` turns a map of options into `KEY=VALUE` pairs, sorted by key so the resulting arguments are stable.
+pub(super) fn serialize_options(
+    options: &HashMap<String, String>,
+) -> Result<Vec<String>, DevpodCommandError> {
+    let mut keys: Vec<&String> = options.keys().collect();
+    keys.sort();
+
+    keys.into_iter()
+        .map(|key| {
+            validate_arg("option", key)?;
+            if key.contains('=') {
+                return Err(DevpodCommandError::InvalidArgument(
+                    "option",
+                    format!("key must not contain '=', got {}", key),
+                ));
+            }
+
+            Ok(format!("{}={}", key, options[key]))
+        })
+        .collect()
+}
+
+/// `validate_env` makes sure all keys of `env` are valid environment variable names.
+pub(super) fn validate_env(env: &HashMap<String, String>) -> Result<(), DevpodCommandError> {
+    for key in env.keys() {
+        if key.is_empty() || key.contains('=') || key.contains('\0') {
+            return Err(DevpodCommandError::InvalidArgument(
+                "env",
+                format!("invalid variable name {:?}", key),
+            ));
         }
     }
-    fn exec(self) -> Result<T, DevpodCommandError>;
+
+    Ok(())
+}
+
+/// `exec_blocking` runs `exec` on the blocking thread pool so tauri commands don't stall the async runtime while the CLI runs.
+pub async fn exec_blocking<C, T>(cmd: C) -> Result<T, DevpodCommandError>
+where
+    C: DevpodCommandConfig<T> + Send + 'static,
+    T: CommandResult + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(move || cmd.exec_as(Caller::Ui))
+        .await
+        .map_err(DevpodCommandError::Join)?
+}
+
+/// `CommandResult` is what a command produces from the output of a successful run.
+pub trait CommandResult: Sized {
+    fn from_output(output: RunOutput) -> Result<Self, DevpodCommandError>;
+}
+
+/// `JsonResult` marks results the CLI prints to stdout as JSON.
+pub trait JsonResult: DeserializeOwned {}
+
+impl<T: JsonResult> CommandResult for T {
+    fn from_output(output: RunOutput) -> Result<Self, DevpodCommandError> {
+        serde_json::from_str(&output.stdout).map_err(DevpodCommandError::Parse)
+    }
+}
+
+impl<T: JsonResult> JsonResult for Option<T> {}
+
+/// Commands that are only run for their side effects ignore their output.
+impl CommandResult for () {
+    fn from_output(_output: RunOutput) -> Result<Self, DevpodCommandError> {
+        Ok(())
+    }
+}
+
+pub trait DevpodCommandConfig<T: CommandResult> {
+    fn config(&self) -> CommandConfig {
+        CommandConfig::default()
+    }
+    /// `exec_with` runs the command with `runner` and builds its result from the output.
+    fn exec_with(self, runner: &dyn CommandRunner) -> Result<T, DevpodCommandError>
+    where
+        Self: Sized,
+    {
+        T::from_output(self.run(runner)?)
+    }
+
+    fn exec(self) -> Result<T, DevpodCommandError>
+    where
+        Self: Sized,
+    {
+        self.exec_as(Caller::App)
+    }
+
+    /// `exec_as` runs the command with the sidecar, recording `caller` in the invocation history.
+    fn exec_as(self, caller: Caller) -> Result<T, DevpodCommandError>
+    where
+        Self: Sized,
+    {
+        self.exec_with(&SidecarRunner::new(caller))
+    }
 
     fn new_command(&self) -> Result<Command, DevpodCommandError> {
+        new_cli_command(&self.config())
+    }
+
+    /// How long the command may run before it gets killed, `None` waits until it terminates.
+    /// Only applies to `run`, streamed commands like `up` run until they terminate or are cancelled.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        None
+    }
+
+    /// `run` waits for the command to finish and collects its output, retrying according to `retry_policy`.
+    /// Fails with `DevpodCommandError::Exit` carrying the details of the invocation if it exits with a non-zero code.
+    fn run(&self, runner: &dyn CommandRunner) -> Result<RunOutput, DevpodCommandError> {
+        let policy = match self.retry_policy() {
+            Some(policy) => policy,
+            None => return self.run_once(runner),
+        };
+
+        let mut backoff = policy.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            match self.run_once(runner) {
+                Err(err) if err.is_retryable() && attempt < policy.attempts => {
+                    warn!(
+                        "Attempt {}/{} failed, retrying in {}ms: {}",
+                        attempt,
+                        policy.attempts,
+                        backoff.as_millis(),
+                        err
+                    );
+                    runner.wait_before_retry(backoff);
+                    backoff = (backoff * 2).min(policy.max_backoff);
+                    attempt += 1;
+                }
+                Err(err) if err.is_retryable() && attempt > 1 => {
+                    return Err(DevpodCommandError::RetriesExhausted {
+                        attempts: attempt,
+                        last: Box::new(err),
+                    })
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn run_once(&self, runner: &dyn CommandRunner) -> Result<RunOutput, DevpodCommandError> {
         let config = self.config();
-        let env_vars: HashMap<String, String> =
-            HashMap::from([(DEVPOD_UI_ENV_VAR.into(), "true".into())]);
+        let started = Instant::now();
+
+        let output = runner.run(&config, self.timeout())?;
+        if !output.success() {
+            return Err(DevpodCommandError::Exit(Box::new(CommandFailure::new(
+                config.redacted_argv(),
+                &output,
+                started,
+            ))));
+        }
+
+        Ok(output)
+    }
+
+    /// `stream` spawns the command and emits every line it writes to stdout or stderr as a `CommandOutput` event.
+    /// The returned handle can be used to wait for the command to terminate, the UI can cancel it via its invocation ID.
+    /// `timeout` doesn't apply, there is no telling how long e.g. building an image takes.
+    fn stream(
+        &self,
+        app_handle: &AppHandle,
+        caller: Caller,
+    ) -> Result<CommandHandle, DevpodCommandError> {
+        let argv = self.config().argv();
+        let started_at = SystemTime::now();
+        let started = Instant::now();
+        let (mut rx, child) = self
+            .new_command()?
+            .spawn()
+            .map_err(DevpodCommandError::Failed)?;
+
+        let invocation_id = new_invocation_id();
+        let state = app_handle.state::<AppState>();
+        state
+            .running_commands
+            .lock()
+            .unwrap()
+            .insert(invocation_id.clone(), child);
+
+        let (exit_tx, exit_rx) = oneshot::channel();
+        let app_handle = app_handle.clone();
+        let id = invocation_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut exit_code = None;
+            while let Some(event) = rx.recv().await {
+                let event = match CommandOutputEvent::from_event(event) {
+                    Some(event) => event,
+                    None => continue,
+                };
+                let is_terminated = event.is_terminated();
+                if let CommandOutputEvent::Terminated { code, .. } = event {
+                    exit_code = code;
+                }
+
+                let output = CommandOutput::new(id.clone(), event);
+                if let Err(err) = app_handle.emit_all(COMMAND_OUTPUT_EVENT, output) {
+                    error!("Failed to emit command output: {}", err);
+                }
 
-        let cmd = Command::new_sidecar(config.binary_name())
-            .map_err(|_| DevpodCommandError::Sidecar)?
-            .envs(env_vars)
-            .args(config.args());
+                if is_terminated {
+                    break;
+                }
+            }
 
-        Ok(cmd)
+            let state = app_handle.state::<AppState>();
+            state.running_commands.lock().unwrap().remove(&id);
+            invocation_history::record(InvocationRecord::new(
+                &argv,
+                caller,
+                started_at,
+                started.elapsed(),
+                exit_code,
+                None,
+            ));
+            let _ = exit_tx.send(exit_code);
+        });
+
+        Ok(CommandHandle::new(invocation_id, exit_rx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::runner::FakeRunner;
+
+    struct SecretCommand {}
+    impl DevpodCommandConfig<()> for SecretCommand {
+        fn config(&self) -> CommandConfig {
+            CommandConfig {
+                args: vec![
+                    "provider",
+                    "use",
+                    "aws",
+                    "--option",
+                    "AWS_ACCESS_KEY_ID=AKIA123",
+                ],
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn should_serialize_exit_error() {
+        let err = DevpodCommandError::Exit(Box::new(CommandFailure {
+            argv: vec!["devpod-cli".into(), "up".into(), "my-workspace".into()],
+            exit_code: Some(1),
+            duration_ms: 42,
+            stderr_tail: vec!["some stderr".into()],
+            cli_message: Some("provider not found".into()),
+        }));
+
+        let value = serde_json::to_value(&err).unwrap();
+
+        assert_eq!(value["kind"], "exit");
+        assert_eq!(
+            value["message"],
+            "command `devpod-cli up my-workspace` exited with code 1: provider not found"
+        );
+        assert_eq!(value["failure"]["exitCode"], 1);
+        assert_eq!(value["failure"]["stderrTail"][0], "some stderr");
+    }
+
+    #[test]
+    fn should_redact_failed_command() {
+        let runner = FakeRunner::new().respond(1, "", "");
+
+        let result = SecretCommand {}.exec_with(&runner);
+
+        match result {
+            Err(DevpodCommandError::Exit(failure)) => {
+                assert_eq!(failure.argv[5], "AWS_ACCESS_KEY_ID=<redacted>")
+            }
+            other => panic!("expected an exit error, got {:?}", other),
+        }
     }
 }
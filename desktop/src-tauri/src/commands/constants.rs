@@ -5,9 +5,46 @@ pub const DEVPOD_BINARY_NAME: &str = "devpod-cli";
 pub(super) const DEVPOD_COMMAND_LIST: &str = "list";
 pub(super) const DEVPOD_COMMAND_PROVIDER: &str = "provider";
 pub(super) const DEVPOD_COMMAND_DELETE: &str = "delete";
+pub(super) const DEVPOD_COMMAND_UP: &str = "up";
+pub(super) const DEVPOD_COMMAND_STOP: &str = "stop";
+pub(super) const DEVPOD_COMMAND_STATUS: &str = "status";
+pub(super) const DEVPOD_COMMAND_BUILD: &str = "build";
+pub(super) const DEVPOD_COMMAND_ADD: &str = "add";
+pub(super) const DEVPOD_COMMAND_UPDATE: &str = "update";
+pub(super) const DEVPOD_COMMAND_USE: &str = "use";
+pub(super) const DEVPOD_COMMAND_OPTIONS: &str = "options";
+pub(super) const DEVPOD_COMMAND_SET_OPTIONS: &str = "set-options";
+pub(super) const DEVPOD_COMMAND_CONTEXT: &str = "context";
+pub(super) const DEVPOD_COMMAND_CREATE: &str = "create";
+pub(super) const DEVPOD_COMMAND_MACHINE: &str = "machine";
+pub(super) const DEVPOD_COMMAND_START: &str = "start";
+pub(super) const DEVPOD_COMMAND_IDE: &str = "ide";
+pub(super) const DEVPOD_COMMAND_VERSION: &str = "version";
 
 // Flags
 pub(super) const OUTPUT_JSON_ARG: &str = "--output=json";
+pub(super) const LOG_OUTPUT_JSON_ARG: &str = "--log-output=json";
+pub(super) const DEBUG_ARG: &str = "--debug";
+pub(super) const FORCE_ARG: &str = "--force";
+pub(super) const RECREATE_ARG: &str = "--recreate";
+pub(super) const SKIP_PUSH_ARG: &str = "--skip-push";
+pub(super) const SINGLE_MACHINE_ARG: &str = "--single-machine";
+pub(super) const RECONFIGURE_ARG: &str = "--reconfigure";
+pub(super) const DRY_ARG: &str = "--dry";
+pub(super) const USE_ARG: &str = "--use=true";
+pub(super) const NO_USE_ARG: &str = "--use=false";
+pub(super) const ID_FLAG: &str = "--id";
+pub(super) const IDE_FLAG: &str = "--ide";
+pub(super) const PROVIDER_FLAG: &str = "--provider";
+pub(super) const PREBUILD_REPOSITORY_FLAG: &str = "--prebuild-repository";
+pub(super) const DEVCONTAINER_PATH_FLAG: &str = "--devcontainer-path";
+pub(super) const REPOSITORY_FLAG: &str = "--repository";
+pub(super) const PLATFORM_FLAG: &str = "--platform";
+pub(super) const NAME_FLAG: &str = "--name";
+pub(super) const OPTION_FLAG: &str = "--option";
+pub(super) const PROVIDER_OPTION_FLAG: &str = "--provider-option";
+pub(super) const CONTEXT_FLAG: &str = "--context";
 
 // Env vars
 pub(super) const DEVPOD_UI_ENV_VAR: &str = "DEVPOD_UI";
+pub const DEVPOD_HOME_ENV_VAR: &str = "DEVPOD_HOME";
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{
+        serialize_options, validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_CONTEXT, DEVPOD_COMMAND_CREATE, LOG_OUTPUT_JSON_ARG,
+        OPTION_FLAG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CreateContextArgs {
+    pub name: String,
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+}
+
+pub struct CreateContextCommand {
+    args: CreateContextArgs,
+    options: Vec<String>,
+}
+impl CreateContextCommand {
+    pub fn new(args: CreateContextArgs) -> Result<Self, DevpodCommandError> {
+        validate_arg("name", &args.name)?;
+        let options = serialize_options(&args.options)?;
+
+        Ok(CreateContextCommand { args, options })
+    }
+}
+impl DevpodCommandConfig<()> for CreateContextCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![
+            DEVPOD_COMMAND_CONTEXT,
+            DEVPOD_COMMAND_CREATE,
+            &self.args.name,
+        ];
+        for option in &self.options {
+            args.extend([OPTION_FLAG, option]);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{
+        serialize_options, validate_arg, validate_env, CommandConfig, DevpodCommandConfig,
+        DevpodCommandError,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_CREATE, DEVPOD_COMMAND_MACHINE, LOG_OUTPUT_JSON_ARG,
+        PROVIDER_FLAG, PROVIDER_OPTION_FLAG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CreateMachineArgs {
+    pub id: String,
+    /// Defaults to the default provider of the active context
+    pub provider_id: Option<String>,
+    #[serde(default)]
+    pub provider_options: HashMap<String, String>,
+    /// Passed to the provider while it creates the machine
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+pub struct CreateMachineCommand {
+    args: CreateMachineArgs,
+    provider_options: Vec<String>,
+}
+impl CreateMachineCommand {
+    pub fn new(args: CreateMachineArgs) -> Result<Self, DevpodCommandError> {
+        validate_env(&args.env)?;
+        validate_arg("id", &args.id)?;
+        if let Some(provider_id) = &args.provider_id {
+            validate_arg("providerId", provider_id)?;
+        }
+        let provider_options = serialize_options(&args.provider_options)?;
+
+        Ok(CreateMachineCommand {
+            args,
+            provider_options,
+        })
+    }
+}
+impl DevpodCommandConfig<()> for CreateMachineCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_MACHINE, DEVPOD_COMMAND_CREATE, &self.args.id];
+        if let Some(provider_id) = &self.args.provider_id {
+            args.extend([PROVIDER_FLAG, provider_id]);
+        }
+        for option in &self.provider_options {
+            args.extend([PROVIDER_OPTION_FLAG, option]);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            env: self.args.env.clone(),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use super::{
+    config::{validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError},
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_CONTEXT, DEVPOD_COMMAND_DELETE, LOG_OUTPUT_JSON_ARG,
+    },
+};
+
+pub struct DeleteContextCommand {
+    name: String,
+}
+impl DeleteContextCommand {
+    pub fn new(name: String) -> Result<Self, DevpodCommandError> {
+        validate_arg("name", &name)?;
+
+        Ok(DeleteContextCommand { name })
+    }
+}
+impl DevpodCommandConfig<()> for DeleteContextCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![
+                DEVPOD_COMMAND_CONTEXT,
+                DEVPOD_COMMAND_DELETE,
+                &self.name,
+                LOG_OUTPUT_JSON_ARG,
+            ],
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use super::{
+    config::{validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError},
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_DELETE, DEVPOD_COMMAND_MACHINE, FORCE_ARG,
+        LOG_OUTPUT_JSON_ARG,
+    },
+};
+
+pub struct DeleteMachineCommand {
+    machine_id: String,
+    force: bool,
+}
+impl DeleteMachineCommand {
+    pub fn new(machine_id: String, force: bool) -> Result<Self, DevpodCommandError> {
+        validate_arg("id", &machine_id)?;
+
+        Ok(DeleteMachineCommand { machine_id, force })
+    }
+}
+impl DevpodCommandConfig<()> for DeleteMachineCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![
+            DEVPOD_COMMAND_MACHINE,
+            DEVPOD_COMMAND_DELETE,
+            &self.machine_id,
+        ];
+        if self.force {
+            args.push(FORCE_ARG);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            ..Default::default()
+        }
+    }
+}
@@ -20,16 +20,36 @@ impl DevpodCommandConfig<()> for DeleteProviderCommand {
                 DEVPOD_COMMAND_DELETE,
                 &self.provider_id,
             ],
+            ..Default::default()
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::runner::FakeRunner;
+
+    #[test]
+    fn should_delete_provider() {
+        let runner = FakeRunner::new().respond(0, "", "");
+
+        DeleteProviderCommand::new("docker".into())
+            .exec_with(&runner)
+            .unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![vec!["devpod-cli", "provider", "delete", "docker"]]
+        );
+    }
+
+    #[test]
+    fn should_fail_if_provider_is_in_use() {
+        let runner = FakeRunner::new().respond(1, "", "provider docker is still in use");
 
-    fn exec(self) -> Result<(), DevpodCommandError> {
-        let cmd = self.new_command()?;
+        let result = DeleteProviderCommand::new("docker".into()).exec_with(&runner);
 
-        cmd.status()
-            .map_err(DevpodCommandError::Failed)?
-            .success()
-            .then_some(())
-            .ok_or_else(|| DevpodCommandError::Exit)
+        assert!(matches!(result, Err(DevpodCommandError::Exit(_))));
     }
 }
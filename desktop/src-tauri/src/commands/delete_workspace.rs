@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{validate_arg, validate_env, CommandConfig, DevpodCommandConfig, DevpodCommandError},
+    constants::{
+        CONTEXT_FLAG, DEBUG_ARG, DEVPOD_BINARY_NAME, DEVPOD_COMMAND_DELETE, FORCE_ARG,
+        LOG_OUTPUT_JSON_ARG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DeleteWorkspaceArgs {
+    pub id: String,
+    /// The context the workspace belongs to, the active one if not set
+    pub context: Option<String>,
+    /// Delete the workspace even if it is not found remotely anymore
+    #[serde(default)]
+    pub force: bool,
+    #[serde(default)]
+    pub debug: bool,
+    /// Passed to the CLI and the provider while the workspace gets deleted
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+pub struct DeleteWorkspaceCommand {
+    args: DeleteWorkspaceArgs,
+}
+impl DeleteWorkspaceCommand {
+    pub fn new(args: DeleteWorkspaceArgs) -> Result<Self, DevpodCommandError> {
+        validate_env(&args.env)?;
+        validate_arg("id", &args.id)?;
+        if let Some(context) = &args.context {
+            validate_arg("context", context)?;
+        }
+
+        Ok(DeleteWorkspaceCommand { args })
+    }
+}
+impl DevpodCommandConfig<()> for DeleteWorkspaceCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_DELETE, &self.args.id];
+        if let Some(context) = &self.args.context {
+            args.extend([CONTEXT_FLAG, context]);
+        }
+        if self.args.force {
+            args.push(FORCE_ARG);
+        }
+        if self.args.debug {
+            args.push(DEBUG_ARG);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            env: self.args.env.clone(),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use super::{
+    config::{
+        validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError, JsonResult,
+        RetryPolicy, READ_TIMEOUT,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_IDE, DEVPOD_COMMAND_OPTIONS, LOG_OUTPUT_JSON_ARG,
+        OUTPUT_JSON_ARG,
+    },
+};
+use crate::ides::IdeOptions;
+
+pub struct IdeOptionsCommand {
+    ide: String,
+}
+impl IdeOptionsCommand {
+    pub fn new(ide: String) -> Result<Self, DevpodCommandError> {
+        validate_arg("ide", &ide)?;
+
+        Ok(IdeOptionsCommand { ide })
+    }
+}
+impl JsonResult for IdeOptions {}
+impl DevpodCommandConfig<IdeOptions> for IdeOptionsCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![
+                DEVPOD_COMMAND_IDE,
+                DEVPOD_COMMAND_OPTIONS,
+                &self.ide,
+                OUTPUT_JSON_ARG,
+                LOG_OUTPUT_JSON_ARG,
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(READ_TIMEOUT)
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::READ)
+    }
+}
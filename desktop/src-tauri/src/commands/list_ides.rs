@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use super::{
+    config::{CommandConfig, DevpodCommandConfig, JsonResult, RetryPolicy, READ_TIMEOUT},
+    constants::{DEVPOD_BINARY_NAME, DEVPOD_COMMAND_IDE, DEVPOD_COMMAND_LIST, OUTPUT_JSON_ARG},
+};
+use crate::ides::Ides;
+
+pub struct ListIdesCommand {}
+impl ListIdesCommand {
+    pub fn new() -> Self {
+        ListIdesCommand {}
+    }
+}
+impl JsonResult for Ides {}
+impl DevpodCommandConfig<Ides> for ListIdesCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![DEVPOD_COMMAND_IDE, DEVPOD_COMMAND_LIST, OUTPUT_JSON_ARG],
+            ..Default::default()
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(READ_TIMEOUT)
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::READ)
+    }
+}
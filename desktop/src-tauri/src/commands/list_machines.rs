@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use super::{
+    config::{CommandConfig, DevpodCommandConfig, JsonResult, RetryPolicy, READ_TIMEOUT},
+    constants::{DEVPOD_BINARY_NAME, DEVPOD_COMMAND_LIST, DEVPOD_COMMAND_MACHINE, OUTPUT_JSON_ARG},
+};
+use crate::machines::MachinesState;
+
+pub struct ListMachinesCommand {}
+impl ListMachinesCommand {
+    pub fn new() -> Self {
+        ListMachinesCommand {}
+    }
+}
+impl JsonResult for MachinesState {}
+impl DevpodCommandConfig<MachinesState> for ListMachinesCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![DEVPOD_COMMAND_MACHINE, DEVPOD_COMMAND_LIST, OUTPUT_JSON_ARG],
+            ..Default::default()
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(READ_TIMEOUT)
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::READ)
+    }
+}
@@ -0,0 +1,40 @@
+use std::time::Duration;
+
+use super::{
+    config::{CommandConfig, DevpodCommandConfig, JsonResult, RetryPolicy, READ_TIMEOUT},
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_LIST, DEVPOD_COMMAND_PROVIDER, LOG_OUTPUT_JSON_ARG,
+        OUTPUT_JSON_ARG,
+    },
+};
+use crate::providers::Providers;
+
+pub struct ListProvidersCommand {}
+impl ListProvidersCommand {
+    pub fn new() -> Self {
+        ListProvidersCommand {}
+    }
+}
+impl JsonResult for Providers {}
+impl DevpodCommandConfig<Providers> for ListProvidersCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![
+                DEVPOD_COMMAND_PROVIDER,
+                DEVPOD_COMMAND_LIST,
+                OUTPUT_JSON_ARG,
+                LOG_OUTPUT_JSON_ARG,
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(READ_TIMEOUT)
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::READ)
+    }
+}
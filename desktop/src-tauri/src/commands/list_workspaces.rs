@@ -1,33 +1,148 @@
+use std::time::Duration;
+
 use super::{
-    config::{CommandConfig, DevpodCommandConfig, DevpodCommandError},
-    constants::{DEVPOD_BINARY_NAME, DEVPOD_COMMAND_LIST, OUTPUT_JSON_ARG},
+    config::{
+        validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError, JsonResult,
+        RetryPolicy, READ_TIMEOUT,
+    },
+    constants::{CONTEXT_FLAG, DEVPOD_BINARY_NAME, DEVPOD_COMMAND_LIST, OUTPUT_JSON_ARG},
 };
 use crate::workspaces::WorkspacesState;
 
-pub struct ListWorkspacesCommand {}
+pub struct ListWorkspacesCommand {
+    /// Lists the workspaces of the active context if not set
+    context: Option<String>,
+}
 impl ListWorkspacesCommand {
     pub fn new() -> Self {
-        ListWorkspacesCommand {}
+        ListWorkspacesCommand { context: None }
     }
 
-    fn deserialize(&self, str: &str) -> Result<WorkspacesState, DevpodCommandError> {
-        serde_json::from_str(str).map_err(DevpodCommandError::Parse)
+    pub fn with_context(context: String) -> Result<Self, DevpodCommandError> {
+        validate_arg("context", &context)?;
+
+        Ok(ListWorkspacesCommand {
+            context: Some(context),
+        })
     }
 }
+impl JsonResult for WorkspacesState {}
 impl DevpodCommandConfig<WorkspacesState> for ListWorkspacesCommand {
     fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_LIST, OUTPUT_JSON_ARG];
+        if let Some(context) = &self.context {
+            args.extend([CONTEXT_FLAG, context]);
+        }
+
         CommandConfig {
             binary_name: DEVPOD_BINARY_NAME,
-            args: vec![DEVPOD_COMMAND_LIST, OUTPUT_JSON_ARG],
+            args,
+            ..Default::default()
         }
     }
 
-    fn exec(self) -> Result<WorkspacesState, DevpodCommandError> {
-        let output = self
-            .new_command()?
-            .output()
-            .map_err(|_| DevpodCommandError::Output)?;
+    fn timeout(&self) -> Option<Duration> {
+        Some(READ_TIMEOUT)
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::READ)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::runner::FakeRunner;
+    use crate::workspaces::WorkspaceKey;
+
+    #[test]
+    fn should_list_workspaces() {
+        let runner = FakeRunner::new().respond(
+            0,
+            r#"[{"id":"my-workspace","provider":{"name":"docker"},"machine":{"machineId":"my-machine"}}]"#,
+            "",
+        );
+
+        let state = ListWorkspacesCommand::new().exec_with(&runner).unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![vec!["devpod-cli", "list", "--output=json"]]
+        );
+        assert_eq!(
+            state.machine_usage().get("my-machine"),
+            Some(&vec![WorkspaceKey::new("my-workspace", None)])
+        );
+    }
+
+    #[test]
+    fn should_list_workspaces_of_context() {
+        let runner = FakeRunner::new().respond(0, "[]", "");
 
-        self.deserialize(&output.stdout)
+        ListWorkspacesCommand::with_context("staging".to_string())
+            .unwrap()
+            .exec_with(&runner)
+            .unwrap();
+
+        assert_eq!(
+            runner.calls(),
+            vec![vec![
+                "devpod-cli",
+                "list",
+                "--output=json",
+                "--context",
+                "staging"
+            ]]
+        );
+        assert!(ListWorkspacesCommand::with_context("--debug".to_string()).is_err());
+    }
+
+    #[test]
+    fn should_fail_on_invalid_output() {
+        let runner = FakeRunner::new().respond(0, "not json", "");
+
+        let result = ListWorkspacesCommand::new().exec_with(&runner);
+
+        assert!(matches!(result, Err(DevpodCommandError::Parse(_))));
+    }
+
+    #[test]
+    fn should_retry_failed_listing() {
+        let runner = FakeRunner::new()
+            .hang()
+            .respond(1, "", "")
+            .respond(0, "[]", "");
+
+        let result = ListWorkspacesCommand::new().exec_with(&runner);
+
+        assert!(result.is_ok());
+        assert_eq!(runner.calls().len(), 3);
+    }
+
+    #[test]
+    fn should_give_up_after_retries() {
+        let fatal =
+            r#"{"time":"2023-06-01T10:00:00Z","message":"context not found","level":"fatal"}"#;
+        let runner = FakeRunner::new()
+            .respond(1, "", fatal)
+            .respond(1, "", fatal)
+            .respond(1, "", fatal);
+
+        let result = ListWorkspacesCommand::new().exec_with(&runner);
+
+        match result {
+            Err(DevpodCommandError::RetriesExhausted { attempts, last }) => {
+                assert_eq!(attempts, 3);
+                match *last {
+                    DevpodCommandError::Exit(failure) => {
+                        assert_eq!(failure.exit_code, Some(1));
+                        assert_eq!(failure.cli_message, Some("context not found".to_string()));
+                    }
+                    other => panic!("expected exit error, got {:?}", other),
+                }
+            }
+            other => panic!("expected retries to be exhausted, got {:?}", other),
+        }
     }
 }
@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use super::{
+    config::{
+        validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError, JsonResult,
+        RetryPolicy, STATUS_TIMEOUT,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_MACHINE, DEVPOD_COMMAND_STATUS, OUTPUT_JSON_ARG,
+    },
+};
+use crate::machines::MachineStatusResult;
+
+pub struct MachineStatusCommand {
+    machine_id: String,
+}
+impl MachineStatusCommand {
+    pub fn new(machine_id: String) -> Result<Self, DevpodCommandError> {
+        validate_arg("id", &machine_id)?;
+
+        Ok(MachineStatusCommand { machine_id })
+    }
+}
+impl JsonResult for MachineStatusResult {}
+impl DevpodCommandConfig<MachineStatusResult> for MachineStatusCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![
+                DEVPOD_COMMAND_MACHINE,
+                DEVPOD_COMMAND_STATUS,
+                &self.machine_id,
+                OUTPUT_JSON_ARG,
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(STATUS_TIMEOUT)
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::READ)
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use super::{
+    config::{
+        validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError, JsonResult,
+        RetryPolicy, READ_TIMEOUT,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_OPTIONS, DEVPOD_COMMAND_PROVIDER, LOG_OUTPUT_JSON_ARG,
+        OUTPUT_JSON_ARG,
+    },
+};
+use crate::providers::ProviderOptions;
+
+pub struct ProviderOptionsCommand {
+    provider_id: String,
+}
+impl ProviderOptionsCommand {
+    pub fn new(provider_id: String) -> Result<Self, DevpodCommandError> {
+        validate_arg("id", &provider_id)?;
+
+        Ok(ProviderOptionsCommand { provider_id })
+    }
+}
+impl JsonResult for ProviderOptions {}
+impl DevpodCommandConfig<ProviderOptions> for ProviderOptionsCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![
+                DEVPOD_COMMAND_PROVIDER,
+                DEVPOD_COMMAND_OPTIONS,
+                &self.provider_id,
+                OUTPUT_JSON_ARG,
+                LOG_OUTPUT_JSON_ARG,
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(READ_TIMEOUT)
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::READ)
+    }
+}
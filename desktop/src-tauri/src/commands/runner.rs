@@ -0,0 +1,331 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        RwLock,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use lazy_static::lazy_static;
+use tauri::api::process::{Command, CommandEvent};
+
+use crate::{
+    invocation_history::{self, Caller, InvocationRecord},
+    util::measure,
+};
+
+use super::{
+    config::{validate_env, CommandConfig, DevpodCommandError},
+    constants::DEVPOD_UI_ENV_VAR,
+};
+
+/// `RunOutput` is everything a finished command left behind.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOutput {
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl RunOutput {
+    pub fn success(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// `CommandRunner` executes a command to completion.
+/// Commands never spawn processes themselves so they can be tested against a `FakeRunner`.
+pub trait CommandRunner: Send + Sync {
+    /// Runs the command to completion. If it's still running after `timeout`, it gets killed.
+    fn run(
+        &self,
+        config: &CommandConfig,
+        timeout: Option<Duration>,
+    ) -> Result<RunOutput, DevpodCommandError>;
+
+    /// Waits `backoff` before the next attempt of a failed command.
+    fn wait_before_retry(&self, backoff: Duration) {
+        thread::sleep(backoff);
+    }
+}
+
+lazy_static! {
+    static ref CLI_PATH_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+    static ref ENVIRONMENT_PROFILE: RwLock<HashMap<String, String>> = RwLock::new(HashMap::new());
+}
+
+/// `set_cli_path_override` makes all commands run the CLI at `path` instead of the bundled sidecar, `None` goes back to the sidecar.
+pub fn set_cli_path_override(path: Option<PathBuf>) {
+    *CLI_PATH_OVERRIDE.write().unwrap() = path;
+}
+
+pub fn cli_path_override() -> Option<PathBuf> {
+    CLI_PATH_OVERRIDE.read().unwrap().clone()
+}
+
+/// `set_environment_profile` sets the environment variables passed to every command.
+pub fn set_environment_profile(env: HashMap<String, String>) -> Result<(), DevpodCommandError> {
+    validate_env(&env)?;
+    *ENVIRONMENT_PROFILE.write().unwrap() = env;
+
+    Ok(())
+}
+
+/// `environment_var` looks up `key` in the environment the CLI runs with.
+pub fn environment_var(key: &str) -> Option<String> {
+    ENVIRONMENT_PROFILE
+        .read()
+        .unwrap()
+        .get(key)
+        .cloned()
+        .or_else(|| std::env::var(key).ok())
+}
+
+/// `command_env` merges the environment `profile`, the variables of the invocation and `DEVPOD_UI`, in that order.
+fn command_env(
+    profile: &HashMap<String, String>,
+    config: &CommandConfig,
+) -> HashMap<String, String> {
+    let mut env_vars = profile.clone();
+    env_vars.extend(config.env().clone());
+    env_vars.insert(DEVPOD_UI_ENV_VAR.into(), "true".into());
+
+    env_vars
+}
+
+/// `new_cli_command` prepares the CLI for `config`, either the bundled sidecar or the binary configured by the user.
+pub(super) fn new_cli_command(config: &CommandConfig) -> Result<Command, DevpodCommandError> {
+    let cmd = match cli_path_override() {
+        Some(path) => Command::new(path.to_string_lossy()),
+        None => {
+            Command::new_sidecar(config.binary_name()).map_err(|_| DevpodCommandError::Sidecar)?
+        }
+    };
+    let env_vars = command_env(&ENVIRONMENT_PROFILE.read().unwrap(), config);
+    let cmd = cmd.envs(env_vars).args(config.args());
+
+    Ok(cmd)
+}
+
+/// `SidecarRunner` runs commands with the `devpod-cli` sidecar bundled with the app, unless the user configured a different CLI.
+/// Every invocation is recorded in the invocation history on behalf of `caller`.
+#[derive(Debug, Clone, Copy)]
+pub struct SidecarRunner {
+    caller: Caller,
+}
+
+impl SidecarRunner {
+    pub fn new(caller: Caller) -> Self {
+        Self { caller }
+    }
+
+    fn run_command(
+        config: &CommandConfig,
+        timeout: Option<Duration>,
+    ) -> Result<RunOutput, DevpodCommandError> {
+        match timeout {
+            Some(timeout) => run_with_timeout(config, timeout),
+            None => {
+                let output = new_cli_command(config)?.output().map_err(|source| {
+                    DevpodCommandError::Output {
+                        argv: config.redacted_argv(),
+                        source,
+                    }
+                })?;
+
+                Ok(RunOutput {
+                    exit_code: output.status.code(),
+                    stdout: output.stdout,
+                    stderr: output.stderr,
+                })
+            }
+        }
+    }
+}
+
+impl CommandRunner for SidecarRunner {
+    fn run(
+        &self,
+        config: &CommandConfig,
+        timeout: Option<Duration>,
+    ) -> Result<RunOutput, DevpodCommandError> {
+        let started_at = SystemTime::now();
+        let (result, duration) = measure(|| Self::run_command(config, timeout));
+
+        let (exit_code, error) = match &result {
+            Ok(output) => (output.exit_code, None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        invocation_history::record(InvocationRecord::new(
+            &config.argv(),
+            self.caller,
+            started_at,
+            duration,
+            exit_code,
+            error,
+        ));
+
+        result
+    }
+}
+
+fn run_with_timeout(
+    config: &CommandConfig,
+    timeout: Duration,
+) -> Result<RunOutput, DevpodCommandError> {
+    let (mut rx, child) =
+        new_cli_command(config)?
+            .spawn()
+            .map_err(|source| DevpodCommandError::Output {
+                argv: config.redacted_argv(),
+                source,
+            })?;
+
+    // the receiver is async, forward its events so we can wait for them with a deadline
+    let (events_tx, events_rx) = mpsc::channel();
+    thread::spawn(move || {
+        while let Some(event) = rx.blocking_recv() {
+            if events_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    let deadline = Instant::now() + timeout;
+    let mut output = RunOutput::default();
+    let mut terminated = false;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match events_rx.recv_timeout(remaining) {
+            Ok(CommandEvent::Stdout(line)) => {
+                output.stdout.push_str(&line);
+                output.stdout.push('\n');
+            }
+            Ok(CommandEvent::Stderr(line)) => {
+                output.stderr.push_str(&line);
+                output.stderr.push('\n');
+            }
+            // the last lines might still be on their way, like `Command::output` we wait until all senders are gone
+            Ok(CommandEvent::Terminated(payload)) => {
+                output.exit_code = payload.code;
+                terminated = true;
+            }
+            Ok(_) => {}
+            // the process exited in time, a child that keeps its output open doesn't make it time out
+            Err(RecvTimeoutError::Timeout) if terminated => return Ok(output),
+            Err(RecvTimeoutError::Timeout) => {
+                let _ = child.kill();
+
+                return Err(DevpodCommandError::Timeout {
+                    argv: config.redacted_argv(),
+                    timeout_ms: timeout.as_millis() as u64,
+                });
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(output),
+        }
+    }
+}
+
+/// `FakeRunner` replays canned outputs in the order they were added and records every command it was asked to run.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct FakeRunner {
+    outputs: std::sync::Mutex<std::collections::VecDeque<Option<RunOutput>>>,
+    calls: std::sync::Mutex<Vec<Vec<String>>>,
+}
+
+#[cfg(test)]
+impl FakeRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn respond(self, exit_code: i32, stdout: &str, stderr: &str) -> Self {
+        self.outputs.lock().unwrap().push_back(Some(RunOutput {
+            exit_code: Some(exit_code),
+            stdout: stdout.to_string(),
+            stderr: stderr.to_string(),
+        }));
+
+        self
+    }
+
+    /// The next command runs into its timeout.
+    pub fn hang(self) -> Self {
+        self.outputs.lock().unwrap().push_back(None);
+
+        self
+    }
+
+    /// The argv of every command run so far, starting with the binary name.
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for FakeRunner {
+    fn run(
+        &self,
+        config: &CommandConfig,
+        timeout: Option<Duration>,
+    ) -> Result<RunOutput, DevpodCommandError> {
+        self.calls.lock().unwrap().push(config.argv());
+
+        let output = self
+            .outputs
+            .lock()
+            .unwrap()
+            .pop_front()
+            .unwrap_or_else(|| panic!("unexpected command: {}", config.argv().join(" ")));
+
+        output.ok_or_else(|| DevpodCommandError::Timeout {
+            argv: config.redacted_argv(),
+            timeout_ms: timeout
+                .map(|timeout| timeout.as_millis() as u64)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Retries happen right away, tests don't need to sit through the backoff.
+    fn wait_before_retry(&self, _backoff: Duration) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_merge_environment() {
+        let profile = HashMap::from([
+            ("HTTPS_PROXY".to_string(), "http://proxy:3128".to_string()),
+            ("DEVPOD_DEBUG".to_string(), "false".to_string()),
+            ("DEVPOD_UI".to_string(), "false".to_string()),
+        ]);
+        let config = CommandConfig {
+            args: vec!["up"],
+            env: HashMap::from([("DEVPOD_DEBUG".to_string(), "true".to_string())]),
+            ..Default::default()
+        };
+
+        let env = command_env(&profile, &config);
+
+        assert_eq!(env["HTTPS_PROXY"], "http://proxy:3128");
+        assert_eq!(env["DEVPOD_DEBUG"], "true");
+        assert_eq!(env["DEVPOD_UI"], "true");
+    }
+
+    #[test]
+    fn should_reject_invalid_variable_names() {
+        let result =
+            set_environment_profile(HashMap::from([("FOO=BAR".to_string(), "baz".to_string())]));
+
+        assert!(matches!(
+            result,
+            Err(DevpodCommandError::InvalidArgument("env", _))
+        ));
+    }
+}
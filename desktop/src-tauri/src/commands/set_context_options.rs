@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{
+        serialize_options, validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_CONTEXT, DEVPOD_COMMAND_SET_OPTIONS,
+        LOG_OUTPUT_JSON_ARG, OPTION_FLAG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SetContextOptionsArgs {
+    /// Defaults to the active context
+    pub name: Option<String>,
+    pub options: HashMap<String, String>,
+}
+
+pub struct SetContextOptionsCommand {
+    args: SetContextOptionsArgs,
+    options: Vec<String>,
+}
+impl SetContextOptionsCommand {
+    pub fn new(args: SetContextOptionsArgs) -> Result<Self, DevpodCommandError> {
+        if let Some(name) = &args.name {
+            validate_arg("name", name)?;
+        }
+        let options = serialize_options(&args.options)?;
+
+        Ok(SetContextOptionsCommand { args, options })
+    }
+}
+impl DevpodCommandConfig<()> for SetContextOptionsCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_CONTEXT, DEVPOD_COMMAND_SET_OPTIONS];
+        if let Some(name) = &self.args.name {
+            args.push(name);
+        }
+        for option in &self.options {
+            args.extend([OPTION_FLAG, option]);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{
+        serialize_options, validate_arg, CommandConfig, CommandResult, DevpodCommandConfig,
+        DevpodCommandError,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_PROVIDER, DEVPOD_COMMAND_SET_OPTIONS, DRY_ARG,
+        LOG_OUTPUT_JSON_ARG, OPTION_FLAG, RECONFIGURE_ARG, SINGLE_MACHINE_ARG,
+    },
+    runner::CommandRunner,
+};
+use crate::providers::ProviderOptions;
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SetProviderOptionsArgs {
+    pub id: String,
+    pub options: HashMap<String, String>,
+    #[serde(default)]
+    pub single_machine: bool,
+    #[serde(default)]
+    pub reconfigure: bool,
+    /// Don't persist the options and return the filled options instead
+    #[serde(default)]
+    pub dry: bool,
+}
+
+pub struct SetProviderOptionsCommand {
+    args: SetProviderOptionsArgs,
+    options: Vec<String>,
+}
+impl SetProviderOptionsCommand {
+    pub fn new(args: SetProviderOptionsArgs) -> Result<Self, DevpodCommandError> {
+        validate_arg("id", &args.id)?;
+        let options = serialize_options(&args.options)?;
+
+        Ok(SetProviderOptionsCommand { args, options })
+    }
+}
+impl DevpodCommandConfig<Option<ProviderOptions>> for SetProviderOptionsCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![
+            DEVPOD_COMMAND_PROVIDER,
+            DEVPOD_COMMAND_SET_OPTIONS,
+            &self.args.id,
+        ];
+        for option in &self.options {
+            args.extend([OPTION_FLAG, option]);
+        }
+        if self.args.single_machine {
+            args.push(SINGLE_MACHINE_ARG);
+        }
+        if self.args.reconfigure {
+            args.push(RECONFIGURE_ARG);
+        }
+        if self.args.dry {
+            args.push(DRY_ARG);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            ..Default::default()
+        }
+    }
+
+    fn exec_with(
+        self,
+        runner: &dyn CommandRunner,
+    ) -> Result<Option<ProviderOptions>, DevpodCommandError> {
+        let output = self.run(runner)?;
+
+        if !self.args.dry {
+            return Ok(None);
+        }
+
+        ProviderOptions::from_output(output).map(Some)
+    }
+}
@@ -0,0 +1,31 @@
+use super::{
+    config::{validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError},
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_MACHINE, DEVPOD_COMMAND_STOP, LOG_OUTPUT_JSON_ARG,
+    },
+};
+
+pub struct StopMachineCommand {
+    machine_id: String,
+}
+impl StopMachineCommand {
+    pub fn new(machine_id: String) -> Result<Self, DevpodCommandError> {
+        validate_arg("id", &machine_id)?;
+
+        Ok(StopMachineCommand { machine_id })
+    }
+}
+impl DevpodCommandConfig<()> for StopMachineCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![
+                DEVPOD_COMMAND_MACHINE,
+                DEVPOD_COMMAND_STOP,
+                &self.machine_id,
+                LOG_OUTPUT_JSON_ARG,
+            ],
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use super::{
+    config::{validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError},
+    constants::{
+        CONTEXT_FLAG, DEBUG_ARG, DEVPOD_BINARY_NAME, DEVPOD_COMMAND_STOP, LOG_OUTPUT_JSON_ARG,
+    },
+};
+
+pub struct StopWorkspaceCommand {
+    workspace_id: String,
+    context: Option<String>,
+    debug: bool,
+}
+impl StopWorkspaceCommand {
+    pub fn new(
+        workspace_id: String,
+        context: Option<String>,
+        debug: bool,
+    ) -> Result<Self, DevpodCommandError> {
+        validate_arg("id", &workspace_id)?;
+        if let Some(context) = &context {
+            validate_arg("context", context)?;
+        }
+
+        Ok(StopWorkspaceCommand {
+            workspace_id,
+            context,
+            debug,
+        })
+    }
+}
+impl DevpodCommandConfig<()> for StopWorkspaceCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_STOP, &self.workspace_id];
+        if let Some(context) = &self.context {
+            args.extend([CONTEXT_FLAG, context]);
+        }
+        if self.debug {
+            args.push(DEBUG_ARG);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            ..Default::default()
+        }
+    }
+}
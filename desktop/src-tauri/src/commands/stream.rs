@@ -0,0 +1,173 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::Serialize;
+use tauri::api::process::{CommandChild, CommandEvent};
+use tokio::sync::oneshot;
+use ts_rs::TS;
+
+use super::{
+    cli_log::{self, LogEvent},
+    DevpodCommandError,
+};
+use crate::AppState;
+
+// WARN: needs to match the event name the UI listens to
+pub const COMMAND_OUTPUT_EVENT: &str = "command_output";
+
+static INVOCATION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+pub(super) fn new_invocation_id() -> String {
+    format!(
+        "{}-{}",
+        chrono::Utc::now().timestamp_millis(),
+        INVOCATION_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CommandOutput {
+    pub invocation_id: String,
+    pub event: CommandOutputEvent,
+    /// The decoded line if the command logged it as JSON
+    pub log: Option<LogEvent>,
+}
+
+impl CommandOutput {
+    pub(super) fn new(invocation_id: String, event: CommandOutputEvent) -> Self {
+        let log = match &event {
+            CommandOutputEvent::Stdout(line) | CommandOutputEvent::Stderr(line) => {
+                cli_log::parse_event(line)
+            }
+            _ => None,
+        };
+
+        Self {
+            invocation_id,
+            event,
+            log,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+#[ts(export)]
+pub enum CommandOutputEvent {
+    Stdout(String),
+    Stderr(String),
+    Error(String),
+    Terminated {
+        code: Option<i32>,
+        signal: Option<i32>,
+    },
+}
+
+impl CommandOutputEvent {
+    pub(super) fn from_event(event: CommandEvent) -> Option<Self> {
+        match event {
+            CommandEvent::Stdout(line) => Some(Self::Stdout(line)),
+            CommandEvent::Stderr(line) => Some(Self::Stderr(line)),
+            CommandEvent::Error(err) => Some(Self::Error(err)),
+            CommandEvent::Terminated(payload) => Some(Self::Terminated {
+                code: payload.code,
+                signal: payload.signal,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        matches!(self, Self::Terminated { .. })
+    }
+}
+
+/// `CommandHandle` identifies a streamed command invocation.
+/// The UI only ever sees the invocation ID and uses it to cancel the command via `cancel_command`.
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CommandHandle {
+    invocation_id: String,
+    #[serde(skip)]
+    #[ts(skip)]
+    exit: Option<oneshot::Receiver<Option<i32>>>,
+}
+
+impl CommandHandle {
+    pub(super) fn new(invocation_id: String, exit: oneshot::Receiver<Option<i32>>) -> Self {
+        Self {
+            invocation_id,
+            exit: Some(exit),
+        }
+    }
+
+    pub fn invocation_id(&self) -> &str {
+        &self.invocation_id
+    }
+
+    /// `take` hands waiting for the command over to a new handle, e.g. to wait in the background while this one goes to the UI.
+    pub fn take(&mut self) -> CommandHandle {
+        CommandHandle {
+            invocation_id: self.invocation_id.clone(),
+            exit: self.exit.take(),
+        }
+    }
+
+    /// Waits until the command terminated and returns its exit code, if there is one.
+    pub async fn wait(&mut self) -> Option<i32> {
+        match self.exit.take() {
+            Some(exit) => exit.await.ok().flatten(),
+            None => None,
+        }
+    }
+}
+
+/// `RunningCommands` keeps track of the child processes of all streamed commands that haven't terminated yet.
+#[derive(Default)]
+pub struct RunningCommands {
+    children: HashMap<String, CommandChild>,
+}
+
+impl std::fmt::Debug for RunningCommands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunningCommands")
+            .field("invocation_ids", &self.children.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl RunningCommands {
+    pub(super) fn insert(&mut self, invocation_id: String, child: CommandChild) {
+        self.children.insert(invocation_id, child);
+    }
+
+    pub(super) fn remove(&mut self, invocation_id: &str) {
+        self.children.remove(invocation_id);
+    }
+
+    pub fn cancel(&mut self, invocation_id: &str) -> Result<(), DevpodCommandError> {
+        let child = self
+            .children
+            .remove(invocation_id)
+            .ok_or_else(|| DevpodCommandError::NotRunning(invocation_id.to_string()))?;
+
+        child.kill().map_err(DevpodCommandError::Failed)
+    }
+}
+
+#[tauri::command]
+pub fn cancel_command(
+    state: tauri::State<'_, AppState>,
+    invocation_id: String,
+) -> Result<(), DevpodCommandError> {
+    state
+        .running_commands
+        .lock()
+        .unwrap()
+        .cancel(&invocation_id)
+}
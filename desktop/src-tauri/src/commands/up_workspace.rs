@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{validate_arg, validate_env, CommandConfig, DevpodCommandConfig, DevpodCommandError},
+    constants::{
+        CONTEXT_FLAG, DEBUG_ARG, DEVCONTAINER_PATH_FLAG, DEVPOD_BINARY_NAME, DEVPOD_COMMAND_UP,
+        IDE_FLAG, ID_FLAG, LOG_OUTPUT_JSON_ARG, PREBUILD_REPOSITORY_FLAG, PROVIDER_FLAG,
+        RECREATE_ARG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct UpWorkspaceArgs {
+    pub id: String,
+    /// Instead of starting a workspace just by ID, start it with a `source/ID` combination
+    pub source: Option<String>,
+    /// The context the workspace belongs to, the active one if not set
+    pub context: Option<String>,
+    pub ide: Option<String>,
+    pub provider_id: Option<String>,
+    #[serde(default)]
+    pub prebuild_repositories: Vec<String>,
+    pub devcontainer_path: Option<String>,
+    #[serde(default)]
+    pub recreate: bool,
+    #[serde(default)]
+    pub debug: bool,
+    /// Passed to the CLI and the provider while the workspace gets created or started
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+pub struct UpWorkspaceCommand {
+    args: UpWorkspaceArgs,
+    prebuild_repositories: Option<String>,
+}
+impl UpWorkspaceCommand {
+    pub fn new(args: UpWorkspaceArgs) -> Result<Self, DevpodCommandError> {
+        validate_env(&args.env)?;
+        validate_arg("id", &args.id)?;
+        if let Some(source) = &args.source {
+            validate_arg("source", source)?;
+        }
+        if let Some(context) = &args.context {
+            validate_arg("context", context)?;
+        }
+        if let Some(ide) = &args.ide {
+            validate_arg("ide", ide)?;
+        }
+        if let Some(provider_id) = &args.provider_id {
+            validate_arg("providerId", provider_id)?;
+        }
+        if let Some(devcontainer_path) = &args.devcontainer_path {
+            validate_arg("devcontainerPath", devcontainer_path)?;
+        }
+        for repository in &args.prebuild_repositories {
+            validate_arg("prebuildRepositories", repository)?;
+        }
+
+        let prebuild_repositories = if args.prebuild_repositories.is_empty() {
+            None
+        } else {
+            Some(args.prebuild_repositories.join(","))
+        };
+
+        Ok(UpWorkspaceCommand {
+            args,
+            prebuild_repositories,
+        })
+    }
+}
+impl DevpodCommandConfig<()> for UpWorkspaceCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_UP];
+        match &self.args.source {
+            Some(source) => args.extend([source.as_str(), ID_FLAG, &self.args.id]),
+            None => args.push(&self.args.id),
+        }
+        if let Some(context) = &self.args.context {
+            args.extend([CONTEXT_FLAG, context]);
+        }
+        if let Some(ide) = &self.args.ide {
+            args.extend([IDE_FLAG, ide]);
+        }
+        if let Some(provider_id) = &self.args.provider_id {
+            args.extend([PROVIDER_FLAG, provider_id]);
+        }
+        if let Some(prebuild_repositories) = &self.prebuild_repositories {
+            args.extend([PREBUILD_REPOSITORY_FLAG, prebuild_repositories]);
+        }
+        if let Some(devcontainer_path) = &self.args.devcontainer_path {
+            args.extend([DEVCONTAINER_PATH_FLAG, devcontainer_path]);
+        }
+        if self.args.recreate {
+            args.push(RECREATE_ARG);
+        }
+        if self.args.debug {
+            args.push(DEBUG_ARG);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            env: self.args.env.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_build_args_from_source() {
+        let cmd = UpWorkspaceCommand::new(UpWorkspaceArgs {
+            id: "my-workspace".into(),
+            source: Some("https://github.com/loft-sh/devpod".into()),
+            ide: Some("vscode".into()),
+            prebuild_repositories: vec!["ghcr.io/a".into(), "ghcr.io/b".into()],
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            cmd.config().args(),
+            &vec![
+                "up",
+                "https://github.com/loft-sh/devpod",
+                "--id",
+                "my-workspace",
+                "--ide",
+                "vscode",
+                "--prebuild-repository",
+                "ghcr.io/a,ghcr.io/b",
+                "--log-output=json"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_build_args_from_id() {
+        let cmd = UpWorkspaceCommand::new(UpWorkspaceArgs {
+            id: "my-workspace".into(),
+            recreate: true,
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            cmd.config().args(),
+            &vec!["up", "my-workspace", "--recreate", "--log-output=json"]
+        );
+
+        let cmd = UpWorkspaceCommand::new(UpWorkspaceArgs {
+            id: "my-workspace".into(),
+            context: Some("staging".into()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            cmd.config().args(),
+            &vec![
+                "up",
+                "my-workspace",
+                "--context",
+                "staging",
+                "--log-output=json"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_flag_injection() {
+        let result = UpWorkspaceCommand::new(UpWorkspaceArgs {
+            id: "--provider-option=FOO=bar".into(),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            result,
+            Err(DevpodCommandError::InvalidArgument("id", _))
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+use super::{
+    config::{validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError},
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_PROVIDER, DEVPOD_COMMAND_UPDATE, LOG_OUTPUT_JSON_ARG,
+        NO_USE_ARG,
+    },
+};
+
+pub struct UpdateProviderCommand {
+    provider_id: String,
+    source: String,
+}
+impl UpdateProviderCommand {
+    pub fn new(provider_id: String, source: String) -> Result<Self, DevpodCommandError> {
+        validate_arg("id", &provider_id)?;
+        validate_arg("source", &source)?;
+
+        Ok(UpdateProviderCommand {
+            provider_id,
+            source,
+        })
+    }
+}
+impl DevpodCommandConfig<()> for UpdateProviderCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![
+                DEVPOD_COMMAND_PROVIDER,
+                DEVPOD_COMMAND_UPDATE,
+                &self.provider_id,
+                &self.source,
+                NO_USE_ARG,
+                LOG_OUTPUT_JSON_ARG,
+            ],
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{
+        serialize_options, validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_IDE, DEVPOD_COMMAND_USE, LOG_OUTPUT_JSON_ARG,
+        OPTION_FLAG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct UseIdeArgs {
+    pub ide: String,
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+}
+
+pub struct UseIdeCommand {
+    args: UseIdeArgs,
+    options: Vec<String>,
+}
+impl UseIdeCommand {
+    pub fn new(args: UseIdeArgs) -> Result<Self, DevpodCommandError> {
+        validate_arg("ide", &args.ide)?;
+        let options = serialize_options(&args.options)?;
+
+        Ok(UseIdeCommand { args, options })
+    }
+}
+impl DevpodCommandConfig<()> for UseIdeCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_IDE, DEVPOD_COMMAND_USE, &self.args.ide];
+        for option in &self.options {
+            args.extend([OPTION_FLAG, option]);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use ts_rs::TS;
+
+use super::{
+    config::{
+        serialize_options, validate_arg, validate_env, CommandConfig, DevpodCommandConfig,
+        DevpodCommandError,
+    },
+    constants::{
+        DEVPOD_BINARY_NAME, DEVPOD_COMMAND_PROVIDER, DEVPOD_COMMAND_USE, LOG_OUTPUT_JSON_ARG,
+        OPTION_FLAG, RECONFIGURE_ARG, SINGLE_MACHINE_ARG,
+    },
+};
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct UseProviderArgs {
+    pub id: String,
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+    /// Use a single machine for all workspaces
+    #[serde(default)]
+    pub single_machine: bool,
+    /// Don't merge the options with the existing provider config
+    #[serde(default)]
+    pub reconfigure: bool,
+    /// Passed to the provider while it initializes
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+pub struct UseProviderCommand {
+    args: UseProviderArgs,
+    options: Vec<String>,
+}
+impl UseProviderCommand {
+    pub fn new(args: UseProviderArgs) -> Result<Self, DevpodCommandError> {
+        validate_env(&args.env)?;
+        validate_arg("id", &args.id)?;
+        let options = serialize_options(&args.options)?;
+
+        Ok(UseProviderCommand { args, options })
+    }
+}
+impl DevpodCommandConfig<()> for UseProviderCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_PROVIDER, DEVPOD_COMMAND_USE, &self.args.id];
+        for option in &self.options {
+            args.extend([OPTION_FLAG, option]);
+        }
+        if self.args.single_machine {
+            args.push(SINGLE_MACHINE_ARG);
+        }
+        if self.args.reconfigure {
+            args.push(RECONFIGURE_ARG);
+        }
+        args.push(LOG_OUTPUT_JSON_ARG);
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            env: self.args.env.clone(),
+        }
+    }
+}
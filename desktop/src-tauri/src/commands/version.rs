@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use super::{
+    config::{CommandConfig, CommandResult, DevpodCommandConfig, DevpodCommandError, READ_TIMEOUT},
+    constants::{DEVPOD_BINARY_NAME, DEVPOD_COMMAND_VERSION},
+    runner::RunOutput,
+};
+
+pub struct VersionCommand {}
+impl VersionCommand {
+    pub fn new() -> Self {
+        VersionCommand {}
+    }
+}
+impl DevpodCommandConfig<String> for VersionCommand {
+    fn config(&self) -> CommandConfig {
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args: vec![DEVPOD_COMMAND_VERSION],
+            ..Default::default()
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(READ_TIMEOUT)
+    }
+}
+
+/// The CLI prints the version as plain text followed by a newline.
+impl CommandResult for String {
+    fn from_output(output: RunOutput) -> Result<Self, DevpodCommandError> {
+        Ok(output.stdout.trim().to_string())
+    }
+}
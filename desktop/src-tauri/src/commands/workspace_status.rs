@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use super::{
+    config::{
+        validate_arg, CommandConfig, DevpodCommandConfig, DevpodCommandError, JsonResult,
+        RetryPolicy, STATUS_TIMEOUT,
+    },
+    constants::{CONTEXT_FLAG, DEVPOD_BINARY_NAME, DEVPOD_COMMAND_STATUS, OUTPUT_JSON_ARG},
+};
+use crate::workspaces::WorkspaceStatusResult;
+
+pub struct WorkspaceStatusCommand {
+    workspace_id: String,
+    context: Option<String>,
+}
+impl WorkspaceStatusCommand {
+    pub fn new(workspace_id: String, context: Option<String>) -> Result<Self, DevpodCommandError> {
+        validate_arg("id", &workspace_id)?;
+        if let Some(context) = &context {
+            validate_arg("context", context)?;
+        }
+
+        Ok(WorkspaceStatusCommand {
+            workspace_id,
+            context,
+        })
+    }
+}
+impl JsonResult for WorkspaceStatusResult {}
+impl DevpodCommandConfig<WorkspaceStatusResult> for WorkspaceStatusCommand {
+    fn config(&self) -> CommandConfig {
+        let mut args = vec![DEVPOD_COMMAND_STATUS, &self.workspace_id, OUTPUT_JSON_ARG];
+        if let Some(context) = &self.context {
+            args.extend([CONTEXT_FLAG, context]);
+        }
+
+        CommandConfig {
+            binary_name: DEVPOD_BINARY_NAME,
+            args,
+            ..Default::default()
+        }
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(STATUS_TIMEOUT)
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy::READ)
+    }
+}
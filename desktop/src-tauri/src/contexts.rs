@@ -0,0 +1,194 @@
+use crate::{
+    commands::{
+        create_context::{CreateContextArgs, CreateContextCommand},
+        delete_context::DeleteContextCommand,
+        exec_blocking,
+        list_contexts::ListContextsCommand,
+        set_context_options::{SetContextOptionsArgs, SetContextOptionsCommand},
+        use_context::{UseContextArgs, UseContextCommand},
+        DevpodCommandConfig, DevpodCommandError,
+    },
+    invocation_history::Caller,
+    refresher::Refresher,
+    system_tray::{SystemTray, SystemTrayClickHandler, ToSystemTraySubmenu},
+    AppHandle, AppState,
+};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::thread;
+use tauri::{CustomMenuItem, Manager, SystemTrayMenu, SystemTraySubmenu};
+use ts_rs::TS;
+
+// not exported to TS, ts-rs doesn't know `transparent` and the UI gets an array of contexts
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct ContextsState {
+    contexts: Vec<Context>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Context {
+    name: String,
+    #[serde(default)]
+    default: bool,
+}
+
+impl ContextsState {
+    pub const IDENTIFIER_PREFIX: &str = "contexts-";
+
+    fn item_id(name: &String) -> String {
+        format!("{}{}", Self::IDENTIFIER_PREFIX, name)
+    }
+
+    pub fn load() -> Result<Self, DevpodCommandError> {
+        let mut state = ListContextsCommand::new().exec_as(Caller::Poller)?;
+        state.contexts.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(state)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.contexts
+            .iter()
+            .map(|context| context.name.clone())
+            .collect()
+    }
+
+    /// The context the CLI currently uses for all commands that don't specify a context explicitly.
+    pub fn active(&self) -> Option<&String> {
+        self.contexts
+            .iter()
+            .find(|context| context.default)
+            .map(|context| &context.name)
+    }
+}
+
+impl ToSystemTraySubmenu for ContextsState {
+    fn to_submenu(&self) -> tauri::SystemTraySubmenu {
+        let mut contexts_menu = SystemTrayMenu::new();
+
+        for context in &self.contexts {
+            let mut item = CustomMenuItem::new(Self::item_id(&context.name), &context.name);
+            if context.default {
+                item = item.selected();
+            }
+            contexts_menu = contexts_menu.add_item(item);
+        }
+
+        SystemTraySubmenu::new("Contexts", contexts_menu)
+    }
+
+    fn on_tray_item_clicked(&self, id: &str) -> Option<SystemTrayClickHandler> {
+        let name = id.replace(Self::IDENTIFIER_PREFIX, "");
+        if self.active() == Some(&name) {
+            return None;
+        }
+
+        Some(Box::new(move |app_handle, _state| {
+            let app_handle = app_handle.clone();
+            let name = name.clone();
+
+            // switching contexts can take a moment, don't block the tray while it happens
+            thread::spawn(move || {
+                let result = UseContextCommand::new(UseContextArgs {
+                    name: name.clone(),
+                    ..Default::default()
+                })
+                .and_then(|cmd| cmd.exec_as(Caller::Tray))
+                .and_then(|_| refresh(&app_handle));
+
+                if let Err(err) = result {
+                    error!("Failed to switch to context {}: {}", name, err);
+                }
+            });
+        }))
+    }
+}
+
+/// `refresh` reloads the contexts from the CLI and rebuilds the tray menu if they changed.
+pub fn refresh(app_handle: &AppHandle) -> Result<(), DevpodCommandError> {
+    let contexts = ContextsState::load()?;
+
+    let state = app_handle.state::<AppState>();
+    let changed = {
+        let current_contexts = &mut *state.contexts.lock().unwrap();
+        if current_contexts != &contexts {
+            *current_contexts = contexts;
+            true
+        } else {
+            false
+        }
+    };
+
+    if changed {
+        SystemTray::new().rebuild_menu(app_handle);
+    }
+
+    Ok(())
+}
+
+async fn refresh_async(app_handle: AppHandle) -> Result<(), DevpodCommandError> {
+    tauri::async_runtime::spawn_blocking(move || refresh(&app_handle))
+        .await
+        .map_err(DevpodCommandError::Join)?
+}
+
+pub fn setup(app_handle: &AppHandle) {
+    // the contexts are part of the CLI config in DEVPOD_HOME, so they are refreshed whenever it changes
+    let refresher = Refresher::from_settings(app_handle);
+    let app_handle = app_handle.clone();
+
+    thread::spawn(move || {
+        refresher.run(|| match refresh(&app_handle) {
+            Ok(()) => true,
+            Err(err) => {
+                warn!("Failed to refresh contexts: {}", err);
+                false
+            }
+        })
+    });
+}
+
+#[tauri::command]
+pub async fn list_contexts(app_handle: AppHandle) -> Result<ContextsState, DevpodCommandError> {
+    refresh_async(app_handle.clone()).await?;
+
+    let state = app_handle.state::<AppState>();
+    let contexts = state.contexts.lock().unwrap().clone();
+
+    Ok(contexts)
+}
+
+#[tauri::command]
+pub async fn create_context(
+    app_handle: AppHandle,
+    args: CreateContextArgs,
+) -> Result<(), DevpodCommandError> {
+    exec_blocking(CreateContextCommand::new(args)?).await?;
+
+    refresh_async(app_handle).await
+}
+
+#[tauri::command]
+pub async fn use_context(
+    app_handle: AppHandle,
+    args: UseContextArgs,
+) -> Result<(), DevpodCommandError> {
+    exec_blocking(UseContextCommand::new(args)?).await?;
+
+    refresh_async(app_handle).await
+}
+
+#[tauri::command]
+pub async fn delete_context(app_handle: AppHandle, name: String) -> Result<(), DevpodCommandError> {
+    exec_blocking(DeleteContextCommand::new(name)?).await?;
+
+    refresh_async(app_handle).await
+}
+
+#[tauri::command]
+pub async fn set_context_options(args: SetContextOptionsArgs) -> Result<(), DevpodCommandError> {
+    exec_blocking(SetContextOptionsCommand::new(args)?).await
+}
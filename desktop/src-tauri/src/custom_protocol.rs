@@ -5,7 +5,7 @@ use tauri::{AppHandle, Manager};
 use thiserror::Error;
 use url::Url;
 
-use crate::{AppState, UiMessage};
+use crate::{commands::DevpodCommandError, ides, invocation_history::Caller, AppState, UiMessage};
 
 // Should match the one from "tauri.config.json" and "Info.plist"
 const APP_IDENTIFIER: &str = "sh.loft.devpod";
@@ -29,6 +29,8 @@ pub enum ParseError {
     UnsupportedHost(String),
     #[error("Unsupported query arguments: {0}")]
     InvalidQuery(String),
+    #[error("Unsupported IDE: {0}")]
+    UnsupportedIde(String),
 }
 
 impl OpenWorkspaceMsg {
@@ -40,14 +42,6 @@ impl OpenWorkspaceMsg {
             source: None,
         }
     }
-    pub fn with_id(id: String) -> OpenWorkspaceMsg {
-        OpenWorkspaceMsg {
-            workspace_id: Some(id),
-            provider_id: None,
-            ide: None,
-            source: None,
-        }
-    }
 }
 
 impl CustomProtocol {
@@ -60,10 +54,13 @@ impl CustomProtocol {
         let app_handle = app.clone();
 
         let result = tauri_plugin_deep_link::register(APP_URL_SCHEME, move |url_scheme| {
-            tauri::async_runtime::block_on(async {
-                info!("App opened with URL: {:?}", url_scheme.to_string());
+            info!("App opened with URL: {:?}", url_scheme.to_string());
+
+            // validating the IDE runs the CLI, so it needs to happen outside of `block_on`
+            let msg = CustomProtocol::parse(&url_scheme.to_string())
+                .and_then(CustomProtocol::validate_ide);
 
-                let msg = CustomProtocol::parse(&url_scheme.to_string());
+            tauri::async_runtime::block_on(async {
                 let app_state = app_handle.state::<AppState>();
 
                 match msg {
@@ -144,6 +141,25 @@ impl CustomProtocol {
         serde_qs::from_str::<OpenWorkspaceMsg>(query)
             .map_err(|_| ParseError::InvalidQuery(query.to_string()))
     }
+
+    fn validate_ide(msg: OpenWorkspaceMsg) -> Result<OpenWorkspaceMsg, ParseError> {
+        let ide = match &msg.ide {
+            Some(ide) => ide,
+            None => return Ok(msg),
+        };
+
+        match ides::ensure_supported(ide, Caller::DeepLink) {
+            Ok(..) => Ok(msg),
+            Err(DevpodCommandError::InvalidArgument(..)) => {
+                Err(ParseError::UnsupportedIde(ide.to_string()))
+            }
+            Err(err) => {
+                // let the UI decide if we can't get the IDEs from the CLI
+                warn!("Failed to validate IDE {}: {}", ide, err);
+                Ok(msg)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
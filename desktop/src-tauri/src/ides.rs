@@ -0,0 +1,128 @@
+use crate::{
+    commands::{
+        exec_blocking,
+        ide_options::IdeOptionsCommand,
+        list_ides::ListIdesCommand,
+        use_ide::{UseIdeArgs, UseIdeCommand},
+        DevpodCommandConfig, DevpodCommandError,
+    },
+    invocation_history::Caller,
+    system_tray::SystemTray,
+    AppHandle,
+};
+use lazy_static::lazy_static;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::RwLock, thread};
+use ts_rs::TS;
+
+lazy_static! {
+    /// The IDEs the CLI supports, loaded once at startup so the tray doesn't have to ask the CLI every time it rebuilds.
+    static ref CATALOG: RwLock<Ides> = RwLock::new(vec![]);
+}
+
+pub type Ides = Vec<Ide>;
+pub type IdeOptions = HashMap<String, IdeOption>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Ide {
+    name: Option<String>,
+    display_name: Option<String>,
+    // spelled out, ts-rs drops the type arguments of aliases
+    #[serde(default)]
+    options: HashMap<String, IdeOption>,
+    icon: Option<String>,
+    icon_dark: Option<String>,
+    #[serde(default)]
+    experimental: bool,
+    #[serde(default)]
+    default: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct IdeOption {
+    name: Option<String>,
+    description: Option<String>,
+    default: Option<String>,
+    #[serde(rename = "enum")]
+    enum_values: Option<Vec<String>>,
+    validation_pattern: Option<String>,
+    validation_message: Option<String>,
+    /// The value the user configured, only set by `ide options`
+    value: Option<String>,
+}
+
+impl Ide {
+    pub fn name(&self) -> Option<&String> {
+        self.name.as_ref()
+    }
+
+    /// The name to show to users, falls back to `name`.
+    pub fn display_name(&self) -> Option<&String> {
+        self.display_name.as_ref().or(self.name.as_ref())
+    }
+}
+
+/// `catalog` returns the IDEs as of the last time we loaded them from the CLI.
+pub fn catalog() -> Ides {
+    CATALOG.read().unwrap().clone()
+}
+
+fn set_catalog(ides: &Ides) -> bool {
+    let mut catalog = CATALOG.write().unwrap();
+    if *catalog == *ides {
+        return false;
+    }
+    *catalog = ides.clone();
+
+    true
+}
+
+pub fn setup(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+
+    thread::spawn(move || match ListIdesCommand::new().exec_as(Caller::App) {
+        Ok(ides) => {
+            if set_catalog(&ides) {
+                SystemTray::new().rebuild_menu(&app_handle);
+            }
+        }
+        Err(err) => warn!("Failed to load IDEs: {}", err),
+    });
+}
+
+/// `ensure_supported` checks `ide` against the IDE catalog of the CLI.
+/// Returns `DevpodCommandError::InvalidArgument` if the CLI doesn't know about it.
+pub fn ensure_supported(ide: &str, caller: Caller) -> Result<(), DevpodCommandError> {
+    let ides = ListIdesCommand::new().exec_as(caller)?;
+
+    if ides.iter().any(|entry| entry.name.as_deref() == Some(ide)) {
+        Ok(())
+    } else {
+        Err(DevpodCommandError::InvalidArgument("ide", ide.to_string()))
+    }
+}
+
+#[tauri::command]
+pub async fn list_ides(app_handle: AppHandle) -> Result<Ides, DevpodCommandError> {
+    let ides = exec_blocking(ListIdesCommand::new()).await?;
+    if set_catalog(&ides) {
+        SystemTray::new().rebuild_menu(&app_handle);
+    }
+
+    Ok(ides)
+}
+
+#[tauri::command]
+pub async fn get_ide_options(ide: String) -> Result<IdeOptions, DevpodCommandError> {
+    exec_blocking(IdeOptionsCommand::new(ide)?).await
+}
+
+#[tauri::command]
+pub async fn use_ide(args: UseIdeArgs) -> Result<(), DevpodCommandError> {
+    exec_blocking(UseIdeCommand::new(args)?).await
+}
@@ -0,0 +1,369 @@
+use crate::{
+    invocation_history::Caller,
+    settings::Settings,
+    workspace_notifications::{self, WorkspaceEvent},
+    workspaces::{self, WorkspaceKey, WorkspaceStatus},
+    AppHandle, AppState,
+};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Mutex, thread, time::Duration};
+use tauri::Manager;
+use ts_rs::TS;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_WARNING_MINUTES: u64 = 10;
+
+lazy_static! {
+    static ref TRACKER: Mutex<IdleTracker> = Mutex::new(IdleTracker::default());
+}
+
+/// `IdleStopOptions` controls when running workspaces are stopped for being idle.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct IdleStopOptions {
+    /// Minutes without activity before a workspace is stopped, idle workspaces keep running if not set
+    #[ts(type = "number | null")]
+    pub timeout: Option<u64>,
+    /// Minutes before the stop to warn about it
+    #[ts(type = "number | null")]
+    pub warning: Option<u64>,
+    /// The workspaces that are never stopped
+    #[serde(default)]
+    pub exemptions: Vec<WorkspaceKey>,
+}
+
+impl IdleStopOptions {
+    fn is_exempt(&self, key: &WorkspaceKey) -> bool {
+        self.exemptions.contains(key)
+    }
+
+    fn timeout(&self) -> Option<chrono::Duration> {
+        self.timeout
+            .filter(|timeout| *timeout > 0)
+            .map(|timeout| chrono::Duration::minutes(timeout as i64))
+    }
+
+    /// `warning` is how long before `timeout` to warn about the stop.
+    /// Warnings that aren't shorter than the timeout would go out as soon as a workspace is idle, these warn halfway instead.
+    fn warning(&self, timeout: chrono::Duration) -> chrono::Duration {
+        let warning =
+            chrono::Duration::minutes(self.warning.unwrap_or(DEFAULT_WARNING_MINUTES) as i64);
+        if warning < timeout {
+            warning
+        } else {
+            timeout / 2
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+enum IdleAction {
+    Warn(WorkspaceKey),
+    Stop(WorkspaceKey),
+}
+
+#[derive(Debug)]
+struct Activity {
+    last_active: DateTime<Utc>,
+    status: Option<WorkspaceStatus>,
+    warned: bool,
+}
+
+impl Activity {
+    fn touch(&mut self, now: DateTime<Utc>) {
+        self.last_active = now;
+        self.warned = false;
+    }
+}
+
+/// `IdleTracker` remembers when each workspace was last active.
+/// Activity is anything that suggests someone uses the workspace: status changes found by polling,
+/// the CLI's `lastUsed`, which SSH sessions update, operations on the workspace and opening it in an IDE.
+#[derive(Debug, Default)]
+struct IdleTracker {
+    workspaces: HashMap<WorkspaceKey, Activity>,
+}
+
+impl IdleTracker {
+    /// `observe` updates the activity of the workspace identified by `key` from its latest known state.
+    /// Workspaces we see for the first time count as active right now.
+    fn observe(
+        &mut self,
+        key: &WorkspaceKey,
+        status: Option<WorkspaceStatus>,
+        last_used: Option<DateTime<Utc>>,
+        busy: bool,
+        now: DateTime<Utc>,
+    ) {
+        let activity = self.workspaces.entry(key.clone()).or_insert(Activity {
+            last_active: now,
+            status,
+            warned: false,
+        });
+
+        if busy || activity.status != status {
+            activity.touch(now);
+        }
+        if let Some(last_used) = last_used {
+            if last_used > activity.last_active {
+                activity.touch(last_used);
+            }
+        }
+        activity.status = status;
+    }
+
+    fn record(&mut self, key: &WorkspaceKey, now: DateTime<Utc>) {
+        if let Some(activity) = self.workspaces.get_mut(key) {
+            activity.touch(now);
+        }
+    }
+
+    /// `retain` forgets all workspaces that are gone.
+    fn retain(&mut self, keys: &[WorkspaceKey]) {
+        self.workspaces.retain(|key, _| keys.contains(key));
+    }
+
+    /// `due` returns the running workspaces to warn about or stop as of `now`, warnings first.
+    fn due(&mut self, options: &IdleStopOptions, now: DateTime<Utc>) -> Vec<IdleAction> {
+        let timeout = match options.timeout() {
+            Some(timeout) => timeout,
+            None => return vec![],
+        };
+
+        let mut actions = vec![];
+        for (key, activity) in &mut self.workspaces {
+            if activity.status != Some(WorkspaceStatus::Running) || options.is_exempt(key) {
+                continue;
+            }
+
+            let idle = now - activity.last_active;
+            if idle >= timeout {
+                // the stop changes the status, which counts as activity again
+                activity.touch(now);
+                actions.push(IdleAction::Stop(key.clone()));
+            } else if idle >= timeout - options.warning(timeout) && !activity.warned {
+                activity.warned = true;
+                actions.push(IdleAction::Warn(key.clone()));
+            }
+        }
+        actions.sort();
+
+        actions
+    }
+}
+
+/// `record_activity` marks the workspace identified by `key` as active right now, e.g. because it was opened.
+pub fn record_activity(key: &WorkspaceKey) {
+    TRACKER.lock().unwrap().record(key, Utc::now());
+}
+
+pub fn setup(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+
+    thread::spawn(move || loop {
+        thread::sleep(CHECK_INTERVAL);
+        check(&app_handle);
+    });
+}
+
+/// `check` updates the activity of all workspaces and warns about or stops the idle ones.
+fn check(app_handle: &AppHandle) {
+    let options = Settings::idle_stop_options(app_handle);
+    let now = Utc::now();
+
+    let state = app_handle.state::<AppState>();
+    let actions = {
+        let workspaces = state.workspaces.lock().unwrap().clone();
+        let operations = state.operations.lock().unwrap();
+        let mut tracker = TRACKER.lock().unwrap();
+
+        let mut keys = vec![];
+        for workspace in workspaces.workspaces() {
+            if let Some(key) = workspace.key() {
+                tracker.observe(
+                    &key,
+                    workspace.status(),
+                    workspace.last_used(),
+                    operations.is_busy(&key),
+                    now,
+                );
+                keys.push(key);
+            }
+        }
+        tracker.retain(&keys);
+
+        tracker.due(&options, now)
+    };
+
+    for action in actions {
+        match action {
+            IdleAction::Warn(key) => {
+                workspace_notifications::notify_idle(app_handle, WorkspaceEvent::IdleWarning, &key)
+            }
+            IdleAction::Stop(key) => {
+                info!("Stopping idle workspace {}", key);
+                let stopped = key.clone();
+                let result = workspaces::stop_and_then(
+                    app_handle,
+                    &key,
+                    Caller::Scheduler,
+                    move |app_handle, succeeded| {
+                        if succeeded {
+                            workspace_notifications::notify_idle(
+                                app_handle,
+                                WorkspaceEvent::IdleStopped,
+                                &stopped,
+                            );
+                        }
+                    },
+                );
+                if let Err(err) = result {
+                    error!("Failed to stop idle workspace {}: {}", key, err);
+                }
+            }
+        }
+    }
+}
+
+/// Replaces the idle stop options.
+#[tauri::command]
+pub fn update_idle_stop_options(app_handle: AppHandle, options: IdleStopOptions) {
+    if let Err(err) = Settings::set_idle_stop_options(&app_handle, &options) {
+        error!("Failed to persist idle stop options: {}", err);
+    }
+}
+
+/// Exempts workspace `id` in `context` from being stopped when idle, or stops exempting it.
+#[tauri::command]
+pub fn set_idle_stop_exemption(
+    app_handle: AppHandle,
+    id: String,
+    context: Option<String>,
+    exempt: bool,
+) {
+    let key = WorkspaceKey::new(id, context);
+    let mut options = Settings::idle_stop_options(&app_handle);
+    options.exemptions.retain(|exemption| exemption != &key);
+    if exempt {
+        options.exemptions.push(key);
+    }
+
+    update_idle_stop_options(app_handle, options);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(id: &str, context: &str) -> WorkspaceKey {
+        WorkspaceKey::new(id, Some(context.to_string()))
+    }
+
+    fn options() -> IdleStopOptions {
+        IdleStopOptions {
+            timeout: Some(60),
+            warning: Some(10),
+            exemptions: vec![key("exempt", "default")],
+        }
+    }
+
+    #[test]
+    fn should_warn_before_stopping_idle_workspaces() {
+        let start = Utc::now();
+        let mut tracker = IdleTracker::default();
+        // exemptions only apply to the context they were made in
+        for (id, context) in [
+            ("idle", "default"),
+            ("exempt", "default"),
+            ("exempt", "staging"),
+        ] {
+            tracker.observe(
+                &key(id, context),
+                Some(WorkspaceStatus::Running),
+                None,
+                false,
+                start,
+            );
+        }
+
+        let minutes = |minutes| start + chrono::Duration::minutes(minutes);
+        assert!(tracker.due(&options(), minutes(49)).is_empty());
+        assert_eq!(
+            tracker.due(&options(), minutes(50)),
+            vec![
+                IdleAction::Warn(key("exempt", "staging")),
+                IdleAction::Warn(key("idle", "default"))
+            ]
+        );
+        assert!(tracker.due(&options(), minutes(55)).is_empty());
+        assert_eq!(
+            tracker.due(&options(), minutes(60)),
+            vec![
+                IdleAction::Stop(key("exempt", "staging")),
+                IdleAction::Stop(key("idle", "default"))
+            ]
+        );
+        assert!(tracker
+            .due(&IdleStopOptions::default(), minutes(600))
+            .is_empty());
+    }
+
+    #[test]
+    fn should_warn_halfway_if_the_warning_is_too_long() {
+        let start = Utc::now();
+        let minutes = |minutes| start + chrono::Duration::minutes(minutes);
+        let options = IdleStopOptions {
+            timeout: Some(10),
+            warning: Some(30),
+            exemptions: vec![],
+        };
+        let mut tracker = IdleTracker::default();
+        let a = key("a", "default");
+        tracker.observe(&a, Some(WorkspaceStatus::Running), None, false, start);
+
+        assert!(tracker.due(&options, minutes(4)).is_empty());
+        assert_eq!(
+            tracker.due(&options, minutes(5)),
+            vec![IdleAction::Warn(a.clone())]
+        );
+        assert_eq!(
+            tracker.due(&options, minutes(10)),
+            vec![IdleAction::Stop(a)]
+        );
+    }
+
+    #[test]
+    fn should_count_activity() {
+        let start = Utc::now();
+        let minutes = |minutes| start + chrono::Duration::minutes(minutes);
+        let mut tracker = IdleTracker::default();
+        let a = key("a", "default");
+        tracker.observe(&a, Some(WorkspaceStatus::Stopped), None, false, start);
+
+        // started by someone else
+        tracker.observe(&a, Some(WorkspaceStatus::Running), None, false, minutes(30));
+        assert!(tracker.due(&options(), minutes(75)).is_empty());
+
+        // SSH session
+        tracker.observe(
+            &a,
+            Some(WorkspaceStatus::Running),
+            Some(minutes(70)),
+            false,
+            minutes(85),
+        );
+        assert!(tracker.due(&options(), minutes(110)).is_empty());
+
+        // opened in an IDE
+        tracker.record(&a, minutes(115));
+        assert!(tracker.due(&options(), minutes(160)).is_empty());
+        assert_eq!(
+            tracker.due(&options(), minutes(175)),
+            vec![IdleAction::Stop(a)]
+        );
+    }
+}
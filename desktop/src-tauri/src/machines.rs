@@ -0,0 +1,297 @@
+use crate::{
+    commands::{
+        create_machine::{CreateMachineArgs, CreateMachineCommand},
+        delete_machine::DeleteMachineCommand,
+        exec_blocking,
+        list_machines::ListMachinesCommand,
+        machine_status::MachineStatusCommand,
+        start_machine::StartMachineCommand,
+        stop_machine::StopMachineCommand,
+        stream::CommandHandle,
+        DevpodCommandConfig, DevpodCommandError,
+    },
+    invocation_history::Caller,
+    refresher::Refresher,
+    system_tray::{SystemTray, SystemTrayClickHandler, ToSystemTraySubmenu},
+    workspaces::WorkspaceKey,
+    AppHandle, AppState,
+};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::thread;
+use tauri::{
+    CustomMenuItem, Manager, SystemTrayMenu, SystemTrayMenuItem, SystemTraySubmenu, Window,
+};
+use ts_rs::TS;
+
+// not exported to TS, ts-rs doesn't know `transparent` and the UI gets an array of machines
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct MachinesState {
+    machines: Vec<Machine>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Machine {
+    id: String,
+    folder: Option<String>,
+    provider: Option<MachineProvider>,
+    creation_timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    context: Option<String>,
+    /// Keys of the workspaces running on this machine, not part of the CLI output
+    #[serde(default)]
+    workspaces: Vec<WorkspaceKey>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MachineProvider {
+    name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, TS)]
+#[ts(export)]
+pub enum MachineStatus {
+    Running,
+    Busy,
+    Stopped,
+    NotFound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MachineStatusResult {
+    pub id: Option<String>,
+    pub context: Option<String>,
+    pub provider: Option<String>,
+    pub state: Option<MachineStatus>,
+}
+
+enum MachineAction {
+    Start,
+    Stop,
+    Delete,
+}
+
+impl MachineAction {
+    fn parse(action: &str) -> Option<Self> {
+        match action {
+            "start" => Some(Self::Start),
+            "stop" => Some(Self::Stop),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+impl MachinesState {
+    pub const IDENTIFIER_PREFIX: &str = "machines-";
+
+    fn item_id(action: &str, id: &String) -> String {
+        format!("{}{}:{}", Self::IDENTIFIER_PREFIX, action, id)
+    }
+
+    pub fn load(app_handle: &AppHandle) -> Result<Self, DevpodCommandError> {
+        let mut state = ListMachinesCommand::new().exec_as(Caller::Poller)?;
+
+        let app_state = app_handle.state::<AppState>();
+        let usage = app_state.workspaces.lock().unwrap().machine_usage();
+        for machine in &mut state.machines {
+            machine.workspaces = usage.get(&machine.id).cloned().unwrap_or_default();
+        }
+
+        Ok(state)
+    }
+}
+
+fn join_keys(keys: &[WorkspaceKey]) -> String {
+    keys.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl ToSystemTraySubmenu for MachinesState {
+    fn to_submenu(&self) -> tauri::SystemTraySubmenu {
+        let mut machines_menu = SystemTrayMenu::new();
+
+        for machine in &self.machines {
+            let mut machine_menu = SystemTrayMenu::new();
+
+            if !machine.workspaces.is_empty() {
+                let used_by = format!("Used by {}", join_keys(&machine.workspaces));
+                machine_menu = machine_menu
+                    .add_item(
+                        CustomMenuItem::new(Self::item_id("info", &machine.id), used_by).disabled(),
+                    )
+                    .add_native_item(SystemTrayMenuItem::Separator);
+            }
+
+            machine_menu = machine_menu
+                .add_item(CustomMenuItem::new(
+                    Self::item_id("start", &machine.id),
+                    "Start",
+                ))
+                .add_item(CustomMenuItem::new(
+                    Self::item_id("stop", &machine.id),
+                    "Stop",
+                ))
+                .add_item(CustomMenuItem::new(
+                    Self::item_id("delete", &machine.id),
+                    "Delete",
+                ));
+
+            machines_menu =
+                machines_menu.add_submenu(SystemTraySubmenu::new(&machine.id, machine_menu));
+        }
+
+        SystemTraySubmenu::new("Machines", machines_menu)
+    }
+
+    fn on_tray_item_clicked(&self, id: &str) -> Option<SystemTrayClickHandler> {
+        let (action, machine_id) = id
+            .strip_prefix(Self::IDENTIFIER_PREFIX)
+            .and_then(|id| id.split_once(':'))?;
+        let action = MachineAction::parse(action)?;
+        let machine = self
+            .machines
+            .iter()
+            .find(|machine| machine.id == machine_id)?;
+        let machine_id = machine.id.clone();
+        let workspaces = machine.workspaces.clone();
+
+        Some(Box::new(move |app_handle, _state| {
+            let app_handle = app_handle.clone();
+            let machine_id = machine_id.clone();
+
+            let result = match action {
+                MachineAction::Start => StartMachineCommand::new(machine_id.clone())
+                    .and_then(|cmd| cmd.stream(&app_handle, Caller::Tray)),
+                MachineAction::Stop => StopMachineCommand::new(machine_id.clone())
+                    .and_then(|cmd| cmd.stream(&app_handle, Caller::Tray)),
+                MachineAction::Delete => {
+                    let confirm_message = if workspaces.is_empty() {
+                        format!("Do you want to delete machine {}?", machine_id)
+                    } else {
+                        format!(
+                            "Machine {} is used by {}. Do you want to delete it anyway?",
+                            machine_id,
+                            join_keys(&workspaces)
+                        )
+                    };
+
+                    // the dialog blocks, so it can't be shown from the main thread
+                    thread::spawn(move || {
+                        let confirmed = tauri::api::dialog::blocking::confirm(
+                            None::<&Window>,
+                            "Delete machine",
+                            confirm_message,
+                        );
+                        if !confirmed {
+                            return;
+                        }
+
+                        if let Err(err) = DeleteMachineCommand::new(machine_id.clone(), false)
+                            .and_then(|cmd| cmd.stream(&app_handle, Caller::Tray))
+                        {
+                            error!("Failed to delete machine {}: {}", machine_id, err);
+                        }
+                    });
+
+                    return;
+                }
+            };
+
+            if let Err(err) = result {
+                error!("Failed to run machine action: {}", err);
+            }
+        }))
+    }
+}
+
+/// `refresh` reloads the machines from the CLI and rebuilds the tray menu if they changed.
+pub fn refresh(app_handle: &AppHandle) -> Result<(), DevpodCommandError> {
+    let machines = MachinesState::load(app_handle)?;
+
+    let state = app_handle.state::<AppState>();
+    let changed = {
+        let current_machines = &mut *state.machines.lock().unwrap();
+        if current_machines != &machines {
+            *current_machines = machines;
+            true
+        } else {
+            false
+        }
+    };
+
+    if changed {
+        SystemTray::new().rebuild_menu(app_handle);
+    }
+
+    Ok(())
+}
+
+pub fn setup(app_handle: &AppHandle) {
+    // the CLI keeps the machines in DEVPOD_HOME, so they are refreshed whenever it changes
+    let refresher = Refresher::from_settings(app_handle);
+    let app_handle = app_handle.clone();
+
+    thread::spawn(move || {
+        refresher.run(|| match refresh(&app_handle) {
+            Ok(()) => true,
+            Err(err) => {
+                warn!("Failed to refresh machines: {}", err);
+                false
+            }
+        })
+    });
+}
+
+#[tauri::command]
+pub fn list_machines(state: tauri::State<'_, AppState>) -> Result<MachinesState, ()> {
+    let machines = state.machines.lock().unwrap();
+
+    Ok(machines.clone())
+}
+
+#[tauri::command]
+pub async fn get_machine_status(id: String) -> Result<MachineStatusResult, DevpodCommandError> {
+    exec_blocking(MachineStatusCommand::new(id)?).await
+}
+
+#[tauri::command]
+pub fn create_machine(
+    app_handle: AppHandle,
+    args: CreateMachineArgs,
+) -> Result<CommandHandle, DevpodCommandError> {
+    CreateMachineCommand::new(args)?.stream(&app_handle, Caller::Ui)
+}
+
+#[tauri::command]
+pub fn start_machine(
+    app_handle: AppHandle,
+    id: String,
+) -> Result<CommandHandle, DevpodCommandError> {
+    StartMachineCommand::new(id)?.stream(&app_handle, Caller::Ui)
+}
+
+#[tauri::command]
+pub fn stop_machine(
+    app_handle: AppHandle,
+    id: String,
+) -> Result<CommandHandle, DevpodCommandError> {
+    StopMachineCommand::new(id)?.stream(&app_handle, Caller::Ui)
+}
+
+#[tauri::command]
+pub fn delete_machine(
+    app_handle: AppHandle,
+    id: String,
+    force: bool,
+) -> Result<CommandHandle, DevpodCommandError> {
+    DeleteMachineCommand::new(id, force)?.stream(&app_handle, Caller::Ui)
+}
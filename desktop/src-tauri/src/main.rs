@@ -8,26 +8,41 @@
 extern crate objc;
 
 mod action_logs;
+mod cli;
 mod commands;
 mod community_contributions;
+mod contexts;
 mod custom_protocol;
 mod fix_env;
+mod ides;
+mod idle_stopper;
 mod install_cli;
+mod invocation_history;
 mod logging;
+mod machines;
+mod operations;
 mod providers;
+mod refresher;
+mod schedules;
 mod settings;
 mod system_tray;
+mod tray_layout;
 mod ui_messages;
 mod ui_ready;
 #[cfg(feature = "enable-updater")]
 mod updates;
 mod util;
 mod window;
+mod workspace_notifications;
 mod workspaces;
 
+use commands::stream::RunningCommands;
 use community_contributions::CommunityContributions;
+use contexts::ContextsState;
 use custom_protocol::CustomProtocol;
 use log::{error, info};
+use machines::MachinesState;
+use operations::OperationScheduler;
 use std::sync::{Arc, Mutex};
 use system_tray::SystemTray;
 use tauri::{Manager, Menu, Wry};
@@ -40,6 +55,10 @@ pub type AppHandle = tauri::AppHandle<Wry>;
 #[derive(Debug)]
 pub struct AppState {
     workspaces: Arc<Mutex<WorkspacesState>>,
+    machines: Arc<Mutex<MachinesState>>,
+    contexts: Arc<Mutex<ContextsState>>,
+    running_commands: Arc<Mutex<RunningCommands>>,
+    operations: Arc<Mutex<OperationScheduler>>,
     community_contributions: Arc<Mutex<CommunityContributions>>,
     ui_messages: Sender<UiMessage>,
     #[cfg(feature = "enable-updater")]
@@ -73,6 +92,10 @@ fn main() -> anyhow::Result<()> {
     let mut app_builder = tauri::Builder::default()
         .manage(AppState {
             workspaces: Arc::new(Mutex::new(WorkspacesState::default())),
+            machines: Arc::new(Mutex::new(MachinesState::default())),
+            contexts: Arc::new(Mutex::new(ContextsState::default())),
+            running_commands: Arc::new(Mutex::new(RunningCommands::default())),
+            operations: Arc::new(Mutex::new(OperationScheduler::default())),
             community_contributions: Arc::new(Mutex::new(contributions)),
             ui_messages: tx.clone(),
             #[cfg(feature = "enable-updater")]
@@ -84,18 +107,33 @@ fn main() -> anyhow::Result<()> {
         })
         .plugin(logging::build_plugin())
         .plugin(tauri_plugin_store::Builder::default().build())
-        .system_tray(system_tray.build_tray(vec![Box::new(&WorkspacesState::default())]))
+        .system_tray(system_tray.build_tray(vec![
+            Box::new(&WorkspacesState::default()),
+            Box::new(&MachinesState::default()),
+            Box::new(&ContextsState::default()),
+        ]))
         .menu(menu)
         .setup(move |app| {
             info!("Setup application");
 
+            // needs to happen before any command runs
+            if let Err(err) = invocation_history::setup(&app.handle()) {
+                error!("Failed to set up invocation history: {}", err);
+            }
+            cli::setup(&app.handle());
             providers::check_dangling_provider(&app.handle());
             let window_helper = window::WindowHelper::new(app.handle());
 
             let window = app.get_window("main").unwrap();
             window_helper.setup(&window);
 
+            tray_layout::setup(&app.handle());
             workspaces::setup(&app.handle(), app.state());
+            idle_stopper::setup(&app.handle());
+            schedules::setup(&app.handle());
+            machines::setup(&app.handle());
+            ides::setup(&app.handle());
+            contexts::setup(&app.handle());
             community_contributions::setup(app.state());
             action_logs::setup(&app.handle())?;
             custom_protocol.setup(app.handle());
@@ -132,9 +170,51 @@ fn main() -> anyhow::Result<()> {
             ui_ready::ui_ready,
             action_logs::write_action_log,
             action_logs::get_action_logs,
+            action_logs::get_action_log_events,
             action_logs::sync_action_logs,
             install_cli::install_cli,
+            cli::set_cli_path,
+            cli::set_environment_profile,
+            invocation_history::get_invocation_history,
+            invocation_history::export_invocation_history,
             community_contributions::get_contributions,
+            commands::stream::cancel_command,
+            workspaces::get_workspaces,
+            tray_layout::update_tray_options,
+            idle_stopper::update_idle_stop_options,
+            idle_stopper::set_idle_stop_exemption,
+            workspace_notifications::set_workspace_notification_enabled,
+            schedules::get_schedules,
+            schedules::create_schedule,
+            schedules::update_schedule,
+            schedules::delete_schedule,
+            schedules::get_schedule_history,
+            workspaces::start_workspace,
+            workspaces::stop_workspace,
+            workspaces::delete_workspace,
+            workspaces::build_workspace,
+            workspaces::get_workspace_status,
+            operations::get_workspace_operations,
+            providers::list_providers,
+            providers::add_provider,
+            providers::update_provider,
+            providers::use_provider,
+            providers::get_provider_options,
+            providers::set_provider_options,
+            machines::list_machines,
+            machines::get_machine_status,
+            machines::create_machine,
+            machines::start_machine,
+            machines::stop_machine,
+            machines::delete_machine,
+            contexts::list_contexts,
+            contexts::create_context,
+            contexts::use_context,
+            contexts::delete_context,
+            contexts::set_context_options,
+            ides::list_ides,
+            ides::get_ide_options,
+            ides::use_ide,
             updates::get_releases,
             updates::get_pending_update,
             updates::check_updates
@@ -146,9 +226,51 @@ fn main() -> anyhow::Result<()> {
             ui_ready::ui_ready,
             action_logs::write_action_log,
             action_logs::get_action_logs,
+            action_logs::get_action_log_events,
             action_logs::sync_action_logs,
             install_cli::install_cli,
+            cli::set_cli_path,
+            cli::set_environment_profile,
+            invocation_history::get_invocation_history,
+            invocation_history::export_invocation_history,
             community_contributions::get_contributions,
+            commands::stream::cancel_command,
+            workspaces::get_workspaces,
+            tray_layout::update_tray_options,
+            idle_stopper::update_idle_stop_options,
+            idle_stopper::set_idle_stop_exemption,
+            workspace_notifications::set_workspace_notification_enabled,
+            schedules::get_schedules,
+            schedules::create_schedule,
+            schedules::update_schedule,
+            schedules::delete_schedule,
+            schedules::get_schedule_history,
+            workspaces::start_workspace,
+            workspaces::stop_workspace,
+            workspaces::delete_workspace,
+            workspaces::build_workspace,
+            workspaces::get_workspace_status,
+            operations::get_workspace_operations,
+            providers::list_providers,
+            providers::add_provider,
+            providers::update_provider,
+            providers::use_provider,
+            providers::get_provider_options,
+            providers::set_provider_options,
+            machines::list_machines,
+            machines::get_machine_status,
+            machines::create_machine,
+            machines::start_machine,
+            machines::stop_machine,
+            machines::delete_machine,
+            contexts::list_contexts,
+            contexts::create_context,
+            contexts::use_context,
+            contexts::delete_context,
+            contexts::set_context_options,
+            ides::list_ides,
+            ides::get_ide_options,
+            ides::use_ide,
         ]);
     }
 
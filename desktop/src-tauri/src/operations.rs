@@ -0,0 +1,525 @@
+use crate::{
+    commands::{stream::CommandHandle, DevpodCommandError},
+    idle_stopper,
+    system_tray::SystemTray,
+    workspace_notifications::{self, LongRunningCommand},
+    workspaces::WorkspaceKey,
+    AppHandle, AppState,
+};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+use tauri::Manager;
+use ts_rs::TS;
+
+// WARN: needs to match the event name the UI listens to
+pub const WORKSPACE_OPERATIONS_EVENT: &str = "workspace_operations";
+
+static OPERATION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+type StartOperation =
+    Box<dyn FnOnce(&AppHandle) -> Result<CommandHandle, DevpodCommandError> + Send>;
+/// Called with whether the operation succeeded once it terminated.
+type OnFinished = Box<dyn FnOnce(&AppHandle, bool) + Send>;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum OperationKind {
+    Up,
+    Stop,
+    Delete,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum OperationStatus {
+    Queued,
+    Running,
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Operation {
+    id: String,
+    workspace_id: String,
+    context: Option<String>,
+    kind: OperationKind,
+    status: OperationStatus,
+    /// Set once the operation started, identifies its `command_output` events
+    invocation_id: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceOperations {
+    workspace_id: String,
+    context: Option<String>,
+    operations: Vec<Operation>,
+}
+
+struct QueuedOperation {
+    operation: Operation,
+    start: Option<StartOperation>,
+    on_finished: Option<OnFinished>,
+}
+
+/// `OperationScheduler` serializes `up`, `stop` and `delete` per workspace.
+/// Operations on the same workspace run one after another, conflicting ones are rejected right away.
+#[derive(Default)]
+pub struct OperationScheduler {
+    queues: HashMap<WorkspaceKey, VecDeque<QueuedOperation>>,
+    /// The kind of the last finished operation per workspace and whether it succeeded
+    finished: HashMap<WorkspaceKey, (OperationKind, bool)>,
+}
+
+impl std::fmt::Debug for OperationScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OperationScheduler")
+            .field("operations", &self.all())
+            .finish()
+    }
+}
+
+impl OperationScheduler {
+    /// `enqueue` adds an operation to the workspace's queue.
+    /// Returns the operation and whether the queue was idle, in which case the caller needs to start it.
+    fn enqueue(
+        &mut self,
+        key: &WorkspaceKey,
+        kind: OperationKind,
+        start: StartOperation,
+        on_finished: Option<OnFinished>,
+    ) -> Result<(Operation, bool), DevpodCommandError> {
+        let queue = self.queues.entry(key.clone()).or_default();
+
+        for queued in queue.iter() {
+            if queued.operation.kind == OperationKind::Delete {
+                return Err(DevpodCommandError::Busy(
+                    key.to_string(),
+                    "workspace is being deleted".to_string(),
+                ));
+            }
+            if queued.operation.kind == kind {
+                return Err(DevpodCommandError::Busy(
+                    key.to_string(),
+                    format!("{:?} is already scheduled", kind).to_lowercase(),
+                ));
+            }
+        }
+
+        let operation = Operation {
+            id: OPERATION_COUNTER
+                .fetch_add(1, Ordering::Relaxed)
+                .to_string(),
+            workspace_id: key.id.clone(),
+            context: key.context.clone(),
+            kind,
+            status: OperationStatus::Queued,
+            invocation_id: None,
+        };
+        let was_idle = queue.is_empty();
+        queue.push_back(QueuedOperation {
+            operation: operation.clone(),
+            start: Some(start),
+            on_finished,
+        });
+
+        Ok((operation, was_idle))
+    }
+
+    /// `start_next` marks the first operation of the workspace as running and hands out its kind and start function.
+    fn start_next(&mut self, key: &WorkspaceKey) -> Option<(OperationKind, StartOperation)> {
+        // the outcome of the previous operation doesn't tell anything about the workspace anymore
+        self.finished.remove(key);
+        let queued = self.queues.get_mut(key)?.front_mut()?;
+        queued.operation.status = OperationStatus::Running;
+
+        Some((queued.operation.kind, queued.start.take()?))
+    }
+
+    fn set_invocation_id(&mut self, key: &WorkspaceKey, invocation_id: String) {
+        if let Some(queued) = self.queues.get_mut(key).and_then(|queue| queue.front_mut()) {
+            queued.operation.invocation_id = Some(invocation_id);
+        }
+    }
+
+    /// `take_on_finished` hands out the callback of the running operation, it is called at most once.
+    fn take_on_finished(&mut self, key: &WorkspaceKey) -> Option<OnFinished> {
+        self.queues
+            .get_mut(key)
+            .and_then(|queue| queue.front_mut())
+            .and_then(|queued| queued.on_finished.take())
+    }
+
+    /// `finish` removes the running operation and remembers whether it `succeeded`.
+    /// Returns whether there are more operations queued for the workspace.
+    fn finish(&mut self, key: &WorkspaceKey, succeeded: bool) -> bool {
+        let queue = match self.queues.get_mut(key) {
+            Some(queue) => queue,
+            None => return false,
+        };
+        if let Some(queued) = queue.pop_front() {
+            self.finished
+                .insert(key.clone(), (queued.operation.kind, succeeded));
+        }
+
+        if queue.is_empty() {
+            self.queues.remove(key);
+            false
+        } else {
+            true
+        }
+    }
+
+    /// `forget_finished` drops the outcome of the last operation, e.g. once the workspace is found running again.
+    pub fn forget_finished(&mut self, key: &WorkspaceKey) {
+        self.finished.remove(key);
+    }
+
+    pub fn is_busy(&self, key: &WorkspaceKey) -> bool {
+        self.queues.contains_key(key)
+    }
+
+    pub fn has_failed(&self, key: &WorkspaceKey) -> bool {
+        matches!(self.finished.get(key), Some((_, false)))
+    }
+
+    /// `expects_stopped` tells whether the workspace is going away or is stopped because of our own operations.
+    pub fn expects_stopped(&self, key: &WorkspaceKey) -> bool {
+        self.is_busy(key)
+            || matches!(
+                self.finished.get(key),
+                Some((OperationKind::Stop | OperationKind::Delete, _))
+            )
+    }
+
+    pub fn operations(&self, key: &WorkspaceKey) -> Vec<Operation> {
+        self.queues
+            .get(key)
+            .map(|queue| {
+                queue
+                    .iter()
+                    .map(|queued| queued.operation.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn all(&self) -> Vec<WorkspaceOperations> {
+        let mut all: Vec<WorkspaceOperations> = self
+            .queues
+            .keys()
+            .map(|key| WorkspaceOperations::new(key, self.operations(key)))
+            .collect();
+        all.sort_by(|a, b| (&a.workspace_id, &a.context).cmp(&(&b.workspace_id, &b.context)));
+
+        all
+    }
+}
+
+impl WorkspaceOperations {
+    fn new(key: &WorkspaceKey, operations: Vec<Operation>) -> Self {
+        WorkspaceOperations {
+            workspace_id: key.id.clone(),
+            context: key.context.clone(),
+            operations,
+        }
+    }
+}
+
+/// `schedule` queues an operation for the workspace identified by `key`, `start` is called once all earlier operations on the workspace terminated.
+pub fn schedule<F>(
+    app_handle: &AppHandle,
+    key: &WorkspaceKey,
+    kind: OperationKind,
+    start: F,
+) -> Result<Operation, DevpodCommandError>
+where
+    F: FnOnce(&AppHandle) -> Result<CommandHandle, DevpodCommandError> + Send + 'static,
+{
+    schedule_and_then(app_handle, key, kind, start, |_, _| {})
+}
+
+/// `schedule_and_then` is `schedule`, `on_finished` is called with whether the operation succeeded once it terminated.
+pub fn schedule_and_then<F, G>(
+    app_handle: &AppHandle,
+    key: &WorkspaceKey,
+    kind: OperationKind,
+    start: F,
+    on_finished: G,
+) -> Result<Operation, DevpodCommandError>
+where
+    F: FnOnce(&AppHandle) -> Result<CommandHandle, DevpodCommandError> + Send + 'static,
+    G: FnOnce(&AppHandle, bool) + Send + 'static,
+{
+    let state = app_handle.state::<AppState>();
+    let (operation, was_idle) = state.operations.lock().unwrap().enqueue(
+        key,
+        kind,
+        Box::new(start),
+        Some(Box::new(on_finished)),
+    )?;
+    operations_changed(app_handle, key);
+    if kind == OperationKind::Up {
+        idle_stopper::record_activity(key);
+    }
+
+    if was_idle {
+        run_queue(app_handle.clone(), key.clone());
+    }
+
+    Ok(operation)
+}
+
+fn run_queue(app_handle: AppHandle, key: WorkspaceKey) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let start = {
+                let state = app_handle.state::<AppState>();
+                let mut operations = state.operations.lock().unwrap();
+                operations.start_next(&key)
+            };
+
+            let mut succeeded = false;
+            if let Some((kind, start)) = start {
+                let started = Instant::now();
+                match start(&app_handle) {
+                    Ok(mut handle) => {
+                        {
+                            let state = app_handle.state::<AppState>();
+                            let mut operations = state.operations.lock().unwrap();
+                            operations.set_invocation_id(&key, handle.invocation_id().to_string());
+                        }
+                        emit_operations(&app_handle, &key);
+
+                        succeeded = handle.wait().await == Some(0);
+                        if kind == OperationKind::Up {
+                            workspace_notifications::notify_command_finished(
+                                &app_handle,
+                                LongRunningCommand::Up,
+                                &key,
+                                succeeded,
+                                started.elapsed(),
+                            );
+                        }
+                    }
+                    Err(err) => error!("Failed to start operation on workspace {}: {}", key, err),
+                }
+            }
+
+            let (has_more, on_finished) = {
+                let state = app_handle.state::<AppState>();
+                let mut operations = state.operations.lock().unwrap();
+                let on_finished = operations.take_on_finished(&key);
+                (operations.finish(&key, succeeded), on_finished)
+            };
+            operations_changed(&app_handle, &key);
+            if let Some(on_finished) = on_finished {
+                on_finished(&app_handle, succeeded);
+            }
+
+            if !has_more {
+                break;
+            }
+        }
+    });
+}
+
+/// `operations_changed` notifies the UI and updates the tray once operations were queued or finished.
+fn operations_changed(app_handle: &AppHandle, key: &WorkspaceKey) {
+    emit_operations(app_handle, key);
+    SystemTray::new().rebuild_menu(app_handle);
+}
+
+fn emit_operations(app_handle: &AppHandle, key: &WorkspaceKey) {
+    let state = app_handle.state::<AppState>();
+    let operations = state.operations.lock().unwrap().operations(key);
+
+    let payload = WorkspaceOperations::new(key, operations);
+    if let Err(err) = app_handle.emit_all(WORKSPACE_OPERATIONS_EVENT, payload) {
+        warn!("Failed to emit workspace operations: {}", err);
+    }
+}
+
+#[tauri::command]
+pub fn get_workspace_operations(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<WorkspaceOperations>, ()> {
+    let operations = state.operations.lock().unwrap();
+
+    Ok(operations.all())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(id: &str) -> WorkspaceKey {
+        WorkspaceKey::new(id, None)
+    }
+
+    fn noop() -> StartOperation {
+        Box::new(|_| Err(DevpodCommandError::Sidecar))
+    }
+
+    #[test]
+    fn should_queue_operations_per_workspace() {
+        let mut scheduler = OperationScheduler::default();
+        let (a, b) = (key("a"), key("b"));
+
+        let (_, was_idle) = scheduler
+            .enqueue(&a, OperationKind::Up, noop(), None)
+            .unwrap();
+        assert!(was_idle);
+        let (_, was_idle) = scheduler
+            .enqueue(&a, OperationKind::Stop, noop(), None)
+            .unwrap();
+        assert!(!was_idle);
+        let (_, was_idle) = scheduler
+            .enqueue(&b, OperationKind::Up, noop(), None)
+            .unwrap();
+        assert!(was_idle);
+
+        assert!(scheduler.start_next(&a).is_some());
+        assert_eq!(
+            scheduler
+                .operations(&a)
+                .iter()
+                .map(|operation| (operation.kind, operation.status))
+                .collect::<Vec<_>>(),
+            vec![
+                (OperationKind::Up, OperationStatus::Running),
+                (OperationKind::Stop, OperationStatus::Queued)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_conflicting_operations() {
+        let mut scheduler = OperationScheduler::default();
+        let a = key("a");
+        scheduler
+            .enqueue(&a, OperationKind::Up, noop(), None)
+            .unwrap();
+
+        assert!(matches!(
+            scheduler.enqueue(&a, OperationKind::Up, noop(), None),
+            Err(DevpodCommandError::Busy(..))
+        ));
+
+        scheduler
+            .enqueue(&a, OperationKind::Delete, noop(), None)
+            .unwrap();
+        assert!(matches!(
+            scheduler.enqueue(&a, OperationKind::Stop, noop(), None),
+            Err(DevpodCommandError::Busy(..))
+        ));
+    }
+
+    #[test]
+    fn should_release_workspace_when_done() {
+        let mut scheduler = OperationScheduler::default();
+        let a = key("a");
+        scheduler
+            .enqueue(&a, OperationKind::Up, noop(), None)
+            .unwrap();
+        scheduler
+            .enqueue(&a, OperationKind::Stop, noop(), None)
+            .unwrap();
+
+        assert!(scheduler.finish(&a, true));
+        assert_eq!(scheduler.operations(&a).len(), 1);
+        assert!(scheduler.is_busy(&a));
+        assert!(!scheduler.finish(&a, true));
+        assert!(scheduler.all().is_empty());
+        assert!(!scheduler.is_busy(&a));
+    }
+
+    #[test]
+    fn should_hand_out_callbacks_once() {
+        let mut scheduler = OperationScheduler::default();
+        let a = key("a");
+        scheduler
+            .enqueue(
+                &a,
+                OperationKind::Stop,
+                noop(),
+                Some(Box::new(|_: &AppHandle, _: bool| {})),
+            )
+            .unwrap();
+        scheduler
+            .enqueue(&a, OperationKind::Up, noop(), None)
+            .unwrap();
+
+        assert!(scheduler.take_on_finished(&a).is_some());
+        assert!(scheduler.take_on_finished(&a).is_none());
+        scheduler.finish(&a, true);
+        assert!(scheduler.take_on_finished(&a).is_none());
+    }
+
+    #[test]
+    fn should_remember_failed_operations() {
+        let mut scheduler = OperationScheduler::default();
+        let a = key("a");
+        scheduler
+            .enqueue(&a, OperationKind::Up, noop(), None)
+            .unwrap();
+        scheduler.finish(&a, false);
+        assert!(scheduler.has_failed(&a));
+
+        scheduler
+            .enqueue(&a, OperationKind::Up, noop(), None)
+            .unwrap();
+        scheduler.finish(&a, true);
+        assert!(!scheduler.has_failed(&a));
+        assert!(!scheduler.expects_stopped(&a));
+
+        scheduler
+            .enqueue(&a, OperationKind::Stop, noop(), None)
+            .unwrap();
+        scheduler.finish(&a, true);
+        assert!(scheduler.expects_stopped(&a));
+        scheduler.forget_finished(&a);
+        assert!(!scheduler.expects_stopped(&a));
+
+        scheduler
+            .enqueue(&a, OperationKind::Up, noop(), None)
+            .unwrap();
+        scheduler.finish(&a, false);
+        scheduler
+            .enqueue(&a, OperationKind::Up, noop(), None)
+            .unwrap();
+        scheduler.start_next(&a);
+        assert!(!scheduler.has_failed(&a));
+    }
+
+    #[test]
+    fn should_queue_operations_per_context() {
+        let mut scheduler = OperationScheduler::default();
+        let default = WorkspaceKey::new("a", Some("default".to_string()));
+        let staging = WorkspaceKey::new("a", Some("staging".to_string()));
+
+        let (_, was_idle) = scheduler
+            .enqueue(&default, OperationKind::Stop, noop(), None)
+            .unwrap();
+        assert!(was_idle);
+        let (_, was_idle) = scheduler
+            .enqueue(&staging, OperationKind::Stop, noop(), None)
+            .unwrap();
+        assert!(was_idle);
+
+        scheduler.finish(&default, false);
+        assert!(scheduler.has_failed(&default));
+        assert!(!scheduler.has_failed(&staging));
+        assert!(scheduler.is_busy(&staging));
+        assert_eq!(scheduler.all().len(), 1);
+    }
+}
@@ -1,7 +1,138 @@
-use crate::commands::{delete_provider::DeleteProviderCommand, DevpodCommandConfig};
+use crate::commands::{
+    add_provider::{AddProviderArgs, AddProviderCommand},
+    delete_provider::DeleteProviderCommand,
+    exec_blocking,
+    list_providers::ListProvidersCommand,
+    provider_options::ProviderOptionsCommand,
+    runner::{CommandRunner, SidecarRunner},
+    set_provider_options::{SetProviderOptionsArgs, SetProviderOptionsCommand},
+    update_provider::UpdateProviderCommand,
+    use_provider::{UseProviderArgs, UseProviderCommand},
+    DevpodCommandConfig, DevpodCommandError,
+};
+use crate::invocation_history::Caller;
 use crate::util::with_data_store;
 use crate::AppHandle;
-use log::info;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+pub type Providers = HashMap<String, Provider>;
+pub type ProviderOptions = HashMap<String, ProviderOption>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Provider {
+    pub config: Option<ProviderConfig>,
+    pub state: Option<ProviderState>,
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProviderConfig {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub source: Option<ProviderSource>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub option_groups: Vec<ProviderOptionGroup>,
+    // spelled out, ts-rs drops the type arguments of aliases
+    #[serde(default)]
+    pub options: HashMap<String, ProviderOption>,
+    pub icon: Option<String>,
+    pub icon_dark: Option<String>,
+    pub home: Option<String>,
+    pub exec: Option<HashMap<String, Vec<String>>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProviderOptionGroup {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub default_visible: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProviderSource {
+    #[serde(default)]
+    pub internal: bool,
+    pub github: Option<String>,
+    pub file: Option<String>,
+    pub url: Option<String>,
+    pub raw: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProviderState {
+    #[serde(default)]
+    pub initialized: bool,
+    #[serde(default)]
+    pub single_machine: bool,
+    #[serde(default)]
+    pub options: HashMap<String, ProviderOptionValue>,
+    pub creation_timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProviderOptionValue {
+    pub value: Option<String>,
+    #[serde(default)]
+    pub user_provided: bool,
+    pub filled: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub children: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProviderOption {
+    /// The option's current value
+    pub value: Option<String>,
+    /// The children generated by this option
+    #[serde(default)]
+    pub children: Vec<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub password: bool,
+    /// Can be one of: string, duration, number or boolean. Defaults to string
+    #[serde(rename = "type")]
+    pub option_type: Option<String>,
+    pub validation_pattern: Option<String>,
+    pub validation_message: Option<String>,
+    #[serde(default)]
+    pub suggestions: Vec<String>,
+    #[serde(rename = "enum")]
+    pub enum_values: Option<Vec<String>>,
+    #[serde(default)]
+    pub hidden: bool,
+    /// The option is resolved once the workspace or machine has been created
+    #[serde(default)]
+    pub local: bool,
+    #[serde(default)]
+    pub global: bool,
+    pub default: Option<String>,
+    pub command: Option<String>,
+    pub sub_options_command: Option<String>,
+}
 
 pub fn check_dangling_provider(app_handle: &AppHandle) {
     let dangling_provider_key = "danglingProviders"; // WARN: needs to match the key defined in typescript
@@ -19,21 +150,118 @@ pub fn check_dangling_provider(app_handle: &AppHandle) {
                     dangling_providers.join(", ")
                 );
 
-                for dangling_provider in dangling_providers.iter() {
-                    if DeleteProviderCommand::new(dangling_provider.clone())
-                        .exec()
-                        .is_ok()
-                        && store.delete(dangling_provider_key).is_ok()
-                    {
-                        info!(
-                            "Successfully deleted dangling provider: {}",
-                            dangling_provider
-                        );
-                        let _ = store.save();
-                    }
+                let deleted = delete_dangling_providers(
+                    &SidecarRunner::new(Caller::App),
+                    &dangling_providers,
+                );
+                if !deleted.is_empty() && store.delete(dangling_provider_key).is_ok() {
+                    let _ = store.save();
                 }
             });
 
         Ok(())
     });
 }
+
+/// `delete_dangling_providers` tries to delete every provider in `dangling_providers` and returns the ones that are gone.
+fn delete_dangling_providers(
+    runner: &dyn CommandRunner,
+    dangling_providers: &[String],
+) -> Vec<String> {
+    dangling_providers
+        .iter()
+        .filter(|dangling_provider| {
+            match DeleteProviderCommand::new(dangling_provider.to_string()).exec_with(runner) {
+                Ok(..) => {
+                    info!(
+                        "Successfully deleted dangling provider: {}",
+                        dangling_provider
+                    );
+                    true
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to delete dangling provider {}: {}",
+                        dangling_provider, err
+                    );
+                    false
+                }
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+#[tauri::command]
+pub async fn list_providers() -> Result<Providers, DevpodCommandError> {
+    exec_blocking(ListProvidersCommand::new()).await
+}
+
+#[tauri::command]
+pub async fn add_provider(args: AddProviderArgs) -> Result<(), DevpodCommandError> {
+    exec_blocking(AddProviderCommand::new(args)?).await
+}
+
+#[tauri::command]
+pub async fn update_provider(id: String, source: String) -> Result<(), DevpodCommandError> {
+    exec_blocking(UpdateProviderCommand::new(id, source)?).await
+}
+
+#[tauri::command]
+pub async fn use_provider(args: UseProviderArgs) -> Result<(), DevpodCommandError> {
+    exec_blocking(UseProviderCommand::new(args)?).await
+}
+
+#[tauri::command]
+pub async fn get_provider_options(id: String) -> Result<ProviderOptions, DevpodCommandError> {
+    exec_blocking(ProviderOptionsCommand::new(id)?).await
+}
+
+/// Returns the filled options if `args.dry` is set, otherwise the options are persisted and nothing is returned.
+#[tauri::command]
+pub async fn set_provider_options(
+    args: SetProviderOptionsArgs,
+) -> Result<Option<ProviderOptions>, DevpodCommandError> {
+    exec_blocking(SetProviderOptionsCommand::new(args)?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::runner::FakeRunner;
+
+    #[test]
+    fn should_delete_dangling_providers() {
+        let runner = FakeRunner::new()
+            .respond(0, "", "")
+            .respond(1, "", "provider not found")
+            .respond(0, "", "");
+        let dangling_providers = vec![
+            "docker".to_string(),
+            "missing".to_string(),
+            "ssh".to_string(),
+        ];
+
+        let deleted = delete_dangling_providers(&runner, &dangling_providers);
+
+        assert_eq!(deleted, vec!["docker".to_string(), "ssh".to_string()]);
+        assert_eq!(
+            runner.calls(),
+            vec![
+                vec!["devpod-cli", "provider", "delete", "docker"],
+                vec!["devpod-cli", "provider", "delete", "missing"],
+                vec!["devpod-cli", "provider", "delete", "ssh"],
+            ]
+        );
+    }
+
+    #[test]
+    fn should_delete_nothing_without_dangling_providers() {
+        let runner = FakeRunner::new();
+
+        let deleted = delete_dangling_providers(&runner, &[]);
+
+        assert!(deleted.is_empty());
+        assert!(runner.calls().is_empty());
+    }
+}
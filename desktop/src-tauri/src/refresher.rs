@@ -0,0 +1,326 @@
+use crate::{
+    commands::{constants::DEVPOD_HOME_ENV_VAR, runner::environment_var},
+    settings::Settings,
+    AppHandle,
+};
+use lazy_static::lazy_static;
+use log::{info, warn};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use ts_rs::TS;
+
+const DEVPOD_HOME_DIR: &str = ".devpod";
+const CONTEXTS_DIR: &str = "contexts";
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Changes usually come in bursts, e.g. when the CLI rewrites a workspace, so we wait for them to settle.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+/// We never refresh more often than this, no matter how many changes come in.
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+lazy_static! {
+    /// Wakes up every refresher, see `environment_changed`.
+    static ref REFRESHERS: Mutex<Vec<Sender<()>>> = Mutex::new(vec![]);
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum RefreshStrategy {
+    /// Refresh when DEVPOD_HOME changes and poll every refresh interval in case we missed something
+    #[default]
+    Watch,
+    /// Only poll every refresh interval
+    Poll,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Trigger {
+    Changed,
+    Interval,
+    Closed,
+}
+
+/// `devpod_home` is the directory the CLI keeps its state in, `DEVPOD_HOME` if set or `~/.devpod` otherwise.
+pub fn devpod_home() -> Option<PathBuf> {
+    environment_var(DEVPOD_HOME_ENV_VAR)
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(DEVPOD_HOME_DIR)))
+}
+
+/// `environment_changed` makes every refresher refresh right away and watch DEVPOD_HOME again, in case the environment profile moved it.
+pub fn environment_changed() {
+    REFRESHERS.lock().unwrap().retain(|tx| tx.send(()).is_ok());
+}
+
+/// `Refresher` calls a refresh function whenever the CLI state in DEVPOD_HOME changes, or after `interval` at the latest.
+pub struct Refresher {
+    strategy: RefreshStrategy,
+    interval: Duration,
+    tx: Sender<()>,
+    changes: Receiver<()>,
+    watched: Vec<(PathBuf, RecursiveMode)>,
+    // dropping the watcher stops it
+    watcher: Option<RecommendedWatcher>,
+}
+
+impl Refresher {
+    pub fn new(strategy: RefreshStrategy, interval: Duration) -> Self {
+        let (tx, changes) = mpsc::channel();
+        REFRESHERS.lock().unwrap().push(tx.clone());
+
+        let mut refresher = Refresher {
+            strategy,
+            interval,
+            tx,
+            changes,
+            watched: vec![],
+            watcher: None,
+        };
+        refresher.watch();
+
+        refresher
+    }
+
+    /// `watch` replaces the watcher whenever the paths to watch changed, because DEVPOD_HOME moved or the CLI created it.
+    fn watch(&mut self) {
+        if self.strategy != RefreshStrategy::Watch {
+            return;
+        }
+        let home = match devpod_home() {
+            Some(home) => home,
+            None => return,
+        };
+        let targets = watch_targets(&home);
+        if targets == self.watched {
+            return;
+        }
+
+        self.watcher = None;
+        match watch_paths(self.tx.clone(), &home, &targets) {
+            Ok(watcher) => {
+                info!("Watching {:?} for changes", home);
+                self.watcher = Some(watcher);
+            }
+            Err(err) => warn!(
+                "Unable to watch DEVPOD_HOME, polling every {}s instead: {}",
+                self.interval.as_secs(),
+                err
+            ),
+        }
+        // a failed watch isn't retried until the paths change
+        self.watched = targets;
+    }
+
+    /// `from_settings` uses the refresh strategy and interval the user configured.
+    pub fn from_settings(app_handle: &AppHandle) -> Self {
+        let strategy = Settings::workspace_refresh_strategy(app_handle);
+        let interval =
+            Settings::workspace_refresh_interval(app_handle).unwrap_or(DEFAULT_REFRESH_INTERVAL);
+
+        Self::new(strategy, interval)
+    }
+
+    /// `run` calls `refresh` right away and then on every change, it never returns.
+    /// `refresh` reports whether it succeeded, failed refreshes are retried with exponential backoff.
+    pub fn run<F: FnMut() -> bool>(mut self, mut refresh: F) {
+        let mut backoff = Backoff::default();
+        loop {
+            self.watch();
+            let started = Instant::now();
+            if refresh() {
+                backoff.reset();
+            } else {
+                std::thread::sleep(backoff.next_delay());
+                // whatever changed in the meantime is picked up by the retry
+                while self.changes.try_recv().is_ok() {}
+                continue;
+            }
+
+            if wait_for_change(&self.changes, self.interval) == Trigger::Closed {
+                std::thread::sleep(self.interval);
+            }
+
+            let elapsed = started.elapsed();
+            if elapsed < MIN_REFRESH_INTERVAL {
+                std::thread::sleep(MIN_REFRESH_INTERVAL - elapsed);
+            }
+        }
+    }
+}
+
+/// `Backoff` doubles the delay between retries with every consecutive failure, up to `MAX_BACKOFF`.
+#[derive(Debug, Default)]
+struct Backoff {
+    failures: u32,
+}
+
+impl Backoff {
+    fn next_delay(&mut self) -> Duration {
+        let delay = INITIAL_BACKOFF
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(MAX_BACKOFF);
+        self.failures = self.failures.saturating_add(1);
+
+        delay
+    }
+
+    fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// `watch_targets` are the paths to watch for changes of the CLI state in `home`.
+/// The CLI creates them on first use, until then we watch the closest parent that exists.
+fn watch_targets(home: &Path) -> Vec<(PathBuf, RecursiveMode)> {
+    let contexts = home.join(CONTEXTS_DIR);
+    if contexts.is_dir() {
+        // the config of the CLI, e.g. the active context, lives at the top level
+        vec![
+            (home.to_path_buf(), RecursiveMode::NonRecursive),
+            (contexts, RecursiveMode::Recursive),
+        ]
+    } else if home.is_dir() {
+        vec![(home.to_path_buf(), RecursiveMode::NonRecursive)]
+    } else {
+        home.parent()
+            .filter(|parent| parent.is_dir())
+            .map(|parent| vec![(parent.to_path_buf(), RecursiveMode::NonRecursive)])
+            .unwrap_or_default()
+    }
+}
+
+fn watch_paths(
+    tx: Sender<()>,
+    home: &Path,
+    targets: &[(PathBuf, RecursiveMode)],
+) -> anyhow::Result<RecommendedWatcher> {
+    let home = home.to_path_buf();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        match event {
+            // reading the workspaces doesn't change them, ignore access events so we don't trigger ourselves
+            Ok(event) if event.kind.is_access() => {}
+            // only the parent of DEVPOD_HOME exists yet, ignore its other entries
+            Ok(event) if !event.paths.iter().any(|path| path.starts_with(&home)) => {}
+            Ok(..) => {
+                let _ = tx.send(());
+            }
+            Err(err) => warn!("Failed to watch DEVPOD_HOME: {}", err),
+        }
+    })?;
+    for (path, mode) in targets {
+        watcher.watch(path, *mode)?;
+    }
+
+    Ok(watcher)
+}
+
+/// `wait_for_change` blocks until a change comes in and no further changes follow within `DEBOUNCE`, or `interval` passed.
+fn wait_for_change(changes: &Receiver<()>, interval: Duration) -> Trigger {
+    match changes.recv_timeout(interval) {
+        Ok(()) => {}
+        Err(RecvTimeoutError::Timeout) => return Trigger::Interval,
+        Err(RecvTimeoutError::Disconnected) => return Trigger::Closed,
+    }
+
+    loop {
+        match changes.recv_timeout(DEBOUNCE) {
+            Ok(()) => continue,
+            Err(..) => return Trigger::Changed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn should_watch_closest_existing_parent() {
+        let root = std::env::temp_dir().join(format!("devpod-refresher-{}", std::process::id()));
+        let home = root.join(".devpod");
+        let contexts = home.join(CONTEXTS_DIR);
+        std::fs::create_dir_all(&root).unwrap();
+
+        assert_eq!(
+            watch_targets(&home),
+            vec![(root.clone(), RecursiveMode::NonRecursive)]
+        );
+
+        std::fs::create_dir_all(&home).unwrap();
+        assert_eq!(
+            watch_targets(&home),
+            vec![(home.clone(), RecursiveMode::NonRecursive)]
+        );
+
+        std::fs::create_dir_all(&contexts).unwrap();
+        assert_eq!(
+            watch_targets(&home),
+            vec![
+                (home.clone(), RecursiveMode::NonRecursive),
+                (contexts, RecursiveMode::Recursive),
+            ]
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn should_debounce_changes() {
+        let (tx, rx) = mpsc::channel();
+        for _ in 0..10 {
+            tx.send(()).unwrap();
+        }
+
+        assert_eq!(
+            wait_for_change(&rx, Duration::from_secs(10)),
+            Trigger::Changed
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn should_back_off_exponentially() {
+        let mut backoff = Backoff::default();
+
+        assert_eq!(backoff.next_delay(), Duration::from_secs(2));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(4));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(8));
+        for _ in 0..100 {
+            backoff.next_delay();
+        }
+        assert_eq!(backoff.next_delay(), MAX_BACKOFF);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn should_fall_back_to_interval() {
+        let (tx, rx) = mpsc::channel();
+        let started = Instant::now();
+
+        assert_eq!(
+            wait_for_change(&rx, Duration::from_millis(50)),
+            Trigger::Interval
+        );
+        assert!(started.elapsed() >= Duration::from_millis(50));
+
+        thread::spawn(move || drop(tx)).join().unwrap();
+        assert_eq!(
+            wait_for_change(&rx, Duration::from_millis(50)),
+            Trigger::Closed
+        );
+    }
+}
@@ -0,0 +1,594 @@
+use crate::{
+    invocation_history::Caller,
+    util::with_data_store,
+    workspaces::{self, WorkspaceKey, WorkspacesState},
+    AppHandle, AppState,
+};
+use chrono::{DateTime, Datelike, Local, Timelike, Utc};
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, str::FromStr, sync::Mutex, thread, time::Duration};
+use tauri::Manager;
+use thiserror::Error;
+use ts_rs::TS;
+
+const SCHEDULES_FILE_NAME: &str = ".schedules.json";
+const SCHEDULES_KEY: &str = "schedules";
+const HISTORY_KEY: &str = "history";
+/// Number of executed scheduled actions we keep.
+const MAX_HISTORY: usize = 200;
+const CHECK_INTERVAL: Duration = Duration::from_secs(20);
+/// Minutes missed while the machine was asleep are only caught up on for this long,
+/// starting a workspace hours after it was due does more harm than good.
+const MAX_CATCH_UP_MINUTES: i64 = 5;
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const DAYS_OF_WEEK: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+lazy_static! {
+    static ref SCHEDULES: Mutex<Vec<Schedule>> = Mutex::new(vec![]);
+    static ref HISTORY: Mutex<VecDeque<ScheduleRun>> = Mutex::new(VecDeque::new());
+}
+
+#[derive(Error, Debug)]
+pub enum ScheduleError {
+    #[error("invalid cron expression: {0}")]
+    InvalidCron(String),
+    #[error("schedule {0} not found")]
+    NotFound(String),
+    #[error("unable to persist schedules: {0}")]
+    Persist(String),
+}
+impl serde::Serialize for ScheduleError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum ScheduleAction {
+    /// Start the workspace without opening it in an IDE
+    Start,
+    Stop,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ScheduleArgs {
+    pub workspace_id: String,
+    /// The context of the workspace, any context if not set
+    pub context: Option<String>,
+    pub action: ScheduleAction,
+    /// When to run the action in local time, e.g. `0 9 * * mon-fri`.
+    /// The five fields are minute, hour, day of month, month and day of week.
+    pub cron: String,
+    pub enabled: bool,
+}
+
+/// `Schedule` runs `action` on a workspace whenever its cron expression matches.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Schedule {
+    pub id: String,
+    pub workspace_id: String,
+    pub context: Option<String>,
+    pub action: ScheduleAction,
+    pub cron: String,
+    pub enabled: bool,
+}
+
+impl Schedule {
+    fn new(id: String, args: ScheduleArgs) -> Result<Self, ScheduleError> {
+        args.cron
+            .parse::<CronExpression>()
+            .map_err(ScheduleError::InvalidCron)?;
+
+        Ok(Schedule {
+            id,
+            workspace_id: args.workspace_id,
+            context: args.context,
+            action: args.action,
+            cron: args.cron,
+            enabled: args.enabled,
+        })
+    }
+}
+
+/// `ScheduleRun` is an action a schedule ran.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ScheduleRun {
+    pub schedule_id: String,
+    pub workspace_id: String,
+    pub action: ScheduleAction,
+    pub ran_at: DateTime<Utc>,
+    /// Set if the action couldn't be run, the outcome of the command itself is in the invocation history
+    pub error: Option<String>,
+}
+
+/// `CronField` is the set of values one field of a cron expression matches.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct CronField {
+    values: u64,
+    /// Whether the field starts with `*`, this changes how the day fields combine
+    unrestricted: bool,
+}
+
+impl CronField {
+    /// `parse` accepts `*`, values, ranges, steps and lists of these, values can be given by `names` starting at `min`.
+    fn parse(field: &str, min: u32, max: u32, names: &[&str]) -> Result<Self, String> {
+        let mut values = 0;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => match step.parse::<u32>() {
+                    Ok(step) if step > 0 => (range, step),
+                    _ => return Err(format!("invalid step in {}", part)),
+                },
+                None => (part, 1),
+            };
+            let (start, end) = match range.split_once('-') {
+                _ if range == "*" => (min, max),
+                Some((start, end)) => (
+                    parse_value(start, min, max, names)?,
+                    parse_value(end, min, max, names)?,
+                ),
+                // `5/15` is short for `5-59/15`
+                None if part.contains('/') => (parse_value(range, min, max, names)?, max),
+                None => {
+                    let value = parse_value(range, min, max, names)?;
+                    (value, value)
+                }
+            };
+            if start > end {
+                return Err(format!("invalid range {}", range));
+            }
+
+            for value in (start..=end).step_by(step as usize) {
+                values |= 1u64 << value;
+            }
+        }
+
+        Ok(CronField {
+            values,
+            unrestricted: field.starts_with('*'),
+        })
+    }
+
+    fn contains(&self, value: u32) -> bool {
+        self.values & (1u64 << value) != 0
+    }
+}
+
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    let parsed = match names
+        .iter()
+        .position(|name| name.eq_ignore_ascii_case(value))
+    {
+        Some(index) => min + index as u32,
+        None => value
+            .parse()
+            .map_err(|_| format!("invalid value {}", value))?,
+    };
+    if parsed < min || parsed > max {
+        return Err(format!("{} is out of range {}-{}", value, min, max));
+    }
+
+    Ok(parsed)
+}
+
+/// `CronExpression` is a standard five field cron expression.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct CronExpression {
+    minute: CronField,
+    hour: CronField,
+    day_of_month: CronField,
+    month: CronField,
+    day_of_week: CronField,
+}
+
+impl FromStr for CronExpression {
+    type Err = String;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("expected 5 fields, got {}", fields.len()));
+        }
+
+        let mut day_of_week = CronField::parse(fields[4], 0, 7, &DAYS_OF_WEEK)?;
+        // both 0 and 7 are sunday
+        if day_of_week.contains(7) {
+            day_of_week.values |= 1;
+        }
+
+        Ok(CronExpression {
+            minute: CronField::parse(fields[0], 0, 59, &[])?,
+            hour: CronField::parse(fields[1], 0, 23, &[])?,
+            day_of_month: CronField::parse(fields[2], 1, 31, &[])?,
+            month: CronField::parse(fields[3], 1, 12, &MONTHS)?,
+            day_of_week,
+        })
+    }
+}
+
+impl CronExpression {
+    fn matches(&self, time: &DateTime<Local>) -> bool {
+        let day_of_month = self.day_of_month.contains(time.day());
+        let day_of_week = self
+            .day_of_week
+            .contains(time.weekday().num_days_from_sunday());
+        // like cron, a day matches either day field if both are restricted
+        let day = if self.day_of_month.unrestricted || self.day_of_week.unrestricted {
+            day_of_month && day_of_week
+        } else {
+            day_of_month || day_of_week
+        };
+
+        day && self.minute.contains(time.minute())
+            && self.hour.contains(time.hour())
+            && self.month.contains(time.month())
+    }
+}
+
+/// `due` returns the enabled schedules that match any minute after `since` up to and including `now`.
+/// Every schedule is returned once, no matter how many of these minutes it matches.
+fn due(schedules: &[Schedule], since: DateTime<Local>, now: DateTime<Local>) -> Vec<&Schedule> {
+    let since = since.max(now - chrono::Duration::minutes(MAX_CATCH_UP_MINUTES));
+    let mut minutes = vec![];
+    let mut minute = start_of_minute(since) + chrono::Duration::minutes(1);
+    while minute <= now {
+        minutes.push(minute);
+        minute += chrono::Duration::minutes(1);
+    }
+
+    schedules
+        .iter()
+        .filter(|schedule| schedule.enabled)
+        .filter(|schedule| match schedule.cron.parse::<CronExpression>() {
+            Ok(cron) => minutes.iter().any(|minute| cron.matches(minute)),
+            Err(..) => false,
+        })
+        .collect()
+}
+
+fn start_of_minute(time: DateTime<Local>) -> DateTime<Local> {
+    time.with_second(0)
+        .and_then(|time| time.with_nanosecond(0))
+        .unwrap_or(time)
+}
+
+pub fn setup(app_handle: &AppHandle) {
+    let _ = with_data_store(app_handle, SCHEDULES_FILE_NAME, |store| {
+        *SCHEDULES.lock().unwrap() = store
+            .get(SCHEDULES_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        *HISTORY.lock().unwrap() = store
+            .get(HISTORY_KEY)
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        Ok(())
+    });
+
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        // schedules for the minute the app starts in don't run, they might have run before a restart
+        let mut since = Local::now();
+        loop {
+            thread::sleep(CHECK_INTERVAL);
+            let now = Local::now();
+            evaluate(&app_handle, since, now);
+            since = now;
+        }
+    });
+}
+
+/// `evaluate` runs the actions of all schedules that became due between `since` and `now`.
+fn evaluate(app_handle: &AppHandle, since: DateTime<Local>, now: DateTime<Local>) {
+    let due: Vec<Schedule> = {
+        let schedules = SCHEDULES.lock().unwrap();
+        due(&schedules, since, now).into_iter().cloned().collect()
+    };
+
+    for schedule in due {
+        info!(
+            "Running schedule {}: {:?} workspace {}",
+            schedule.id, schedule.action, schedule.workspace_id
+        );
+        let result = run(app_handle, &schedule);
+        if let Err(err) = &result {
+            error!(
+                "Failed to run schedule {} on workspace {}: {}",
+                schedule.id, schedule.workspace_id, err
+            );
+        }
+
+        record(
+            app_handle,
+            ScheduleRun {
+                schedule_id: schedule.id,
+                workspace_id: schedule.workspace_id,
+                action: schedule.action,
+                ran_at: Utc::now(),
+                error: result.err(),
+            },
+        );
+    }
+}
+
+/// `target` is the workspace `schedule` runs on, in the context it was found in.
+/// Schedules without a context only run if their workspace ID is unique across all contexts.
+fn target(workspaces: &WorkspacesState, schedule: &Schedule) -> Result<WorkspaceKey, String> {
+    let matching = workspaces.matching(&schedule.workspace_id, schedule.context.as_deref());
+    match matching.as_slice() {
+        [key] => Ok(key.clone()),
+        // `up` with an unknown ID would create a new workspace from it
+        [] => Err("workspace not found".to_string()),
+        _ => Err(
+            "workspace exists in more than one context, set the context of the schedule"
+                .to_string(),
+        ),
+    }
+}
+
+/// `run` queues the action of `schedule` through the same commands and operation queue the UI uses.
+fn run(app_handle: &AppHandle, schedule: &Schedule) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let key = target(&state.workspaces.lock().unwrap(), schedule)?;
+
+    let result = match schedule.action {
+        ScheduleAction::Start => workspaces::start(app_handle, &key, Caller::Scheduler),
+        ScheduleAction::Stop => workspaces::stop(app_handle, &key, Caller::Scheduler),
+    };
+
+    result.map(|_| ()).map_err(|err| err.to_string())
+}
+
+fn record(app_handle: &AppHandle, run: ScheduleRun) {
+    let mut history = HISTORY.lock().unwrap();
+    history.push_back(run);
+    while history.len() > MAX_HISTORY {
+        history.pop_front();
+    }
+
+    if let Err(err) = persist(app_handle, HISTORY_KEY, &*history) {
+        warn!("Failed to record scheduled action: {}", err);
+    }
+}
+
+fn persist<T: Serialize>(
+    app_handle: &AppHandle,
+    key: &str,
+    value: &T,
+) -> Result<(), ScheduleError> {
+    let value =
+        serde_json::to_value(value).map_err(|err| ScheduleError::Persist(err.to_string()))?;
+
+    with_data_store(app_handle, SCHEDULES_FILE_NAME, |store| {
+        store.insert(key.to_string(), value)?;
+        store.save()
+    })
+    .map_err(|err| ScheduleError::Persist(err.to_string()))
+}
+
+/// `update_schedules` applies `f` to a copy of the schedules and only keeps the result once it's persisted.
+fn update_schedules<T, F: FnOnce(&mut Vec<Schedule>) -> Result<T, ScheduleError>>(
+    app_handle: &AppHandle,
+    f: F,
+) -> Result<T, ScheduleError> {
+    let mut schedules = SCHEDULES.lock().unwrap();
+    let mut updated = schedules.clone();
+    let result = f(&mut updated)?;
+    persist(app_handle, SCHEDULES_KEY, &updated)?;
+    *schedules = updated;
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_schedules() -> Vec<Schedule> {
+    SCHEDULES.lock().unwrap().clone()
+}
+
+#[tauri::command]
+pub fn create_schedule(
+    app_handle: AppHandle,
+    args: ScheduleArgs,
+) -> Result<Schedule, ScheduleError> {
+    update_schedules(&app_handle, |schedules| {
+        let mut id = Utc::now().timestamp_millis();
+        while schedules
+            .iter()
+            .any(|schedule| schedule.id == id.to_string())
+        {
+            id += 1;
+        }
+
+        let schedule = Schedule::new(id.to_string(), args)?;
+        schedules.push(schedule.clone());
+
+        Ok(schedule)
+    })
+}
+
+#[tauri::command]
+pub fn update_schedule(
+    app_handle: AppHandle,
+    id: String,
+    args: ScheduleArgs,
+) -> Result<Schedule, ScheduleError> {
+    update_schedules(&app_handle, |schedules| {
+        let existing = schedules
+            .iter_mut()
+            .find(|schedule| schedule.id == id)
+            .ok_or_else(|| ScheduleError::NotFound(id.clone()))?;
+        *existing = Schedule::new(id.clone(), args)?;
+
+        Ok(existing.clone())
+    })
+}
+
+#[tauri::command]
+pub fn delete_schedule(app_handle: AppHandle, id: String) -> Result<(), ScheduleError> {
+    update_schedules(&app_handle, |schedules| {
+        let count = schedules.len();
+        schedules.retain(|schedule| schedule.id != id);
+        if schedules.len() == count {
+            return Err(ScheduleError::NotFound(id.clone()));
+        }
+
+        Ok(())
+    })
+}
+
+/// The latest scheduled actions first, only the ones of `schedule_id` if set.
+#[tauri::command]
+pub fn get_schedule_history(schedule_id: Option<String>, limit: Option<usize>) -> Vec<ScheduleRun> {
+    HISTORY
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .filter(|run| schedule_id.iter().all(|id| &run.schedule_id == id))
+        .take(limit.unwrap_or(MAX_HISTORY))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // 2023-05-01 is a monday
+        Local
+            .with_ymd_and_hms(2023, 5, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn schedule(id: &str, cron: &str) -> Schedule {
+        Schedule {
+            id: id.to_string(),
+            workspace_id: "workspace".to_string(),
+            context: None,
+            action: ScheduleAction::Start,
+            cron: cron.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn should_parse_cron_expressions() {
+        let cron: CronExpression = "*/15 9-17 * jan,MAR-may 1-5".parse().unwrap();
+        assert_eq!(cron.minute.values, 1 | 1 << 15 | 1 << 30 | 1 << 45);
+        assert!(cron.hour.contains(9) && cron.hour.contains(17) && !cron.hour.contains(18));
+        assert!(cron.month.contains(1) && cron.month.contains(4) && !cron.month.contains(2));
+        assert!(cron.day_of_month.unrestricted);
+
+        let cron: CronExpression = "0 0 * * 7".parse().unwrap();
+        assert!(cron.day_of_week.contains(0));
+
+        for invalid in [
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(invalid.parse::<CronExpression>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn should_match_weekdays() {
+        let start: CronExpression = "0 9 * * mon-fri".parse().unwrap();
+        assert!(start.matches(&at(1, 9, 0)));
+        assert!(start.matches(&at(5, 9, 0)));
+        assert!(!start.matches(&at(6, 9, 0)));
+        assert!(!start.matches(&at(1, 9, 1)));
+
+        // the 1st or any sunday
+        let either: CronExpression = "0 19 1 * sun".parse().unwrap();
+        assert!(either.matches(&at(1, 19, 0)));
+        assert!(either.matches(&at(7, 19, 0)));
+        assert!(!either.matches(&at(2, 19, 0)));
+    }
+
+    #[test]
+    fn should_target_the_workspace_in_its_context() {
+        let workspaces: WorkspacesState = serde_json::from_str(
+            r#"[{"id":"workspace","context":"default"},{"id":"workspace","context":"staging"},{"id":"other","context":"staging"}]"#,
+        )
+        .unwrap();
+        let mut schedule = schedule("start", "0 9 * * *");
+
+        assert!(target(&workspaces, &schedule).is_err());
+
+        schedule.context = Some("staging".to_string());
+        assert_eq!(
+            target(&workspaces, &schedule),
+            Ok(WorkspaceKey::new("workspace", Some("staging".to_string())))
+        );
+
+        schedule.workspace_id = "other".to_string();
+        schedule.context = None;
+        assert_eq!(
+            target(&workspaces, &schedule),
+            Ok(WorkspaceKey::new("other", Some("staging".to_string())))
+        );
+
+        schedule.workspace_id = "unknown".to_string();
+        assert!(target(&workspaces, &schedule).is_err());
+    }
+
+    #[test]
+    fn should_run_due_schedules_once() {
+        let mut disabled = schedule("disabled", "0 9 * * *");
+        disabled.enabled = false;
+        let schedules = vec![
+            schedule("start", "0 9 * * *"),
+            schedule("every_minute", "* * * * *"),
+            schedule("stop", "0 19 * * *"),
+            disabled,
+        ];
+        let ids = |due: Vec<&Schedule>| -> Vec<String> {
+            due.into_iter()
+                .map(|schedule| schedule.id.clone())
+                .collect()
+        };
+
+        let due_at_nine = due(
+            &schedules,
+            at(1, 8, 59) + chrono::Duration::seconds(50),
+            at(1, 9, 0),
+        );
+        assert_eq!(ids(due_at_nine), vec!["start", "every_minute"]);
+        // the minute was checked already
+        let again = due(
+            &schedules,
+            at(1, 9, 0),
+            at(1, 9, 0) + chrono::Duration::seconds(20),
+        );
+        assert!(again.is_empty());
+
+        // woke up a few minutes late
+        assert_eq!(ids(due(&schedules, at(1, 8, 58), at(1, 9, 3))).len(), 2);
+        // woke up too late to catch up
+        assert_eq!(
+            ids(due(&schedules, at(1, 8, 58), at(1, 9, 30))),
+            vec!["every_minute"]
+        );
+    }
+}
@@ -1,7 +1,16 @@
 #![allow(dead_code)]
 
-use crate::{util::with_data_store, AppHandle};
+use crate::{
+    idle_stopper::IdleStopOptions,
+    refresher::RefreshStrategy,
+    tray_layout::{TrayGrouping, TrayOptions, TraySorting},
+    util::with_data_store,
+    workspace_notifications::WorkspaceEvent,
+    workspaces::WorkspaceKey,
+    AppHandle,
+};
 use serde::Serialize;
+use std::{collections::HashMap, time::Duration};
 use ts_rs::TS;
 
 const SETTINGS_FILE_NAME: &str = ".settings.json";
@@ -24,6 +33,25 @@ pub struct Settings {
     experimental_fleet: bool,
     #[serde(rename = "experimental_jupyterNotebooks")]
     experimental_jupyter_notebooks: bool,
+    cli_path: Option<String>,
+    environment_profile: HashMap<String, String>,
+    workspace_refresh_strategy: RefreshStrategy,
+    /// Seconds between polls, the fallback when watching for changes
+    #[ts(type = "number | null")]
+    workspace_refresh_interval: Option<u64>,
+    tray_grouping: TrayGrouping,
+    tray_sorting: TraySorting,
+    tray_favorites: Vec<WorkspaceKey>,
+    tray_recent_limit: Option<usize>,
+    /// Desktop notifications per workspace event, all of them are enabled unless turned off
+    workspace_notifications: HashMap<WorkspaceEvent, bool>,
+    /// Minutes without activity before a running workspace is stopped
+    #[ts(type = "number | null")]
+    idle_stop_timeout: Option<u64>,
+    /// Minutes before an idle stop to warn about it
+    #[ts(type = "number | null")]
+    idle_stop_warning: Option<u64>,
+    idle_stop_exemptions: Vec<WorkspaceKey>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -58,4 +86,218 @@ impl Settings {
 
         return is_enabled;
     }
+
+    /// The CLI binary to use instead of the bundled sidecar, if the user configured one.
+    pub fn cli_path(app_handle: &AppHandle) -> Option<String> {
+        let mut cli_path = None;
+        let _ = with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            cli_path = store
+                .get("cliPath")
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(String::from);
+
+            Ok(())
+        });
+
+        return cli_path;
+    }
+
+    pub fn set_cli_path(app_handle: &AppHandle, cli_path: Option<&str>) -> anyhow::Result<()> {
+        with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            match cli_path {
+                Some(cli_path) => store.insert("cliPath".to_string(), cli_path.into())?,
+                None => {
+                    store.delete("cliPath")?;
+                }
+            };
+
+            store.save()
+        })
+    }
+
+    /// Environment variables passed to every CLI command.
+    pub fn environment_profile(app_handle: &AppHandle) -> HashMap<String, String> {
+        let mut environment_profile = HashMap::new();
+        let _ = with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            environment_profile = store
+                .get("environmentProfile")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+
+            Ok(())
+        });
+
+        return environment_profile;
+    }
+
+    /// How the workspaces are kept up to date.
+    pub fn workspace_refresh_strategy(app_handle: &AppHandle) -> RefreshStrategy {
+        let mut strategy = RefreshStrategy::default();
+        let _ = with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            strategy = store
+                .get("workspaceRefreshStrategy")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+
+            Ok(())
+        });
+
+        return strategy;
+    }
+
+    pub fn workspace_refresh_interval(app_handle: &AppHandle) -> Option<Duration> {
+        let mut interval = None;
+        let _ = with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            interval = store
+                .get("workspaceRefreshInterval")
+                .and_then(|v| v.as_u64())
+                .filter(|v| *v > 0)
+                .map(Duration::from_secs);
+
+            Ok(())
+        });
+
+        return interval;
+    }
+
+    /// How workspaces are laid out in the tray menu.
+    pub fn tray_options(app_handle: &AppHandle) -> TrayOptions {
+        let mut options = TrayOptions::default();
+        let _ = with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            options = TrayOptions {
+                grouping: store
+                    .get("trayGrouping")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
+                sorting: store
+                    .get("traySorting")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
+                favorites: store
+                    .get("trayFavorites")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
+                recent_limit: store
+                    .get("trayRecentLimit")
+                    .and_then(|v| v.as_u64())
+                    .filter(|v| *v > 0)
+                    .map(|v| v as usize),
+            };
+
+            Ok(())
+        });
+
+        return options;
+    }
+
+    pub fn set_tray_options(app_handle: &AppHandle, options: &TrayOptions) -> anyhow::Result<()> {
+        let grouping = serde_json::to_value(options.grouping)?;
+        let sorting = serde_json::to_value(options.sorting)?;
+        let favorites = serde_json::to_value(&options.favorites)?;
+        with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            store.insert("trayGrouping".to_string(), grouping)?;
+            store.insert("traySorting".to_string(), sorting)?;
+            store.insert("trayFavorites".to_string(), favorites)?;
+            match options.recent_limit {
+                Some(limit) => store.insert("trayRecentLimit".to_string(), limit.into())?,
+                None => {
+                    store.delete("trayRecentLimit")?;
+                }
+            };
+
+            store.save()
+        })
+    }
+
+    /// When idle workspaces are stopped.
+    pub fn idle_stop_options(app_handle: &AppHandle) -> IdleStopOptions {
+        let mut options = IdleStopOptions::default();
+        let _ = with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            options = IdleStopOptions {
+                timeout: store.get("idleStopTimeout").and_then(|v| v.as_u64()),
+                warning: store.get("idleStopWarning").and_then(|v| v.as_u64()),
+                exemptions: store
+                    .get("idleStopExemptions")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
+            };
+
+            Ok(())
+        });
+
+        return options;
+    }
+
+    pub fn set_idle_stop_options(
+        app_handle: &AppHandle,
+        options: &IdleStopOptions,
+    ) -> anyhow::Result<()> {
+        let exemptions = serde_json::to_value(&options.exemptions)?;
+        with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            for (key, value) in [
+                ("idleStopTimeout", options.timeout),
+                ("idleStopWarning", options.warning),
+            ] {
+                match value {
+                    Some(value) => store.insert(key.to_string(), value.into())?,
+                    None => {
+                        store.delete(key)?;
+                    }
+                };
+            }
+            store.insert("idleStopExemptions".to_string(), exemptions)?;
+
+            store.save()
+        })
+    }
+
+    /// Whether to show a desktop notification for `event`, enabled by default.
+    pub fn workspace_notification_enabled(app_handle: &AppHandle, event: WorkspaceEvent) -> bool {
+        Self::workspace_notifications(app_handle)
+            .get(&event)
+            .copied()
+            .unwrap_or(true)
+    }
+
+    fn workspace_notifications(app_handle: &AppHandle) -> HashMap<WorkspaceEvent, bool> {
+        let mut notifications = HashMap::new();
+        let _ = with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            notifications = store
+                .get("workspaceNotifications")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+
+            Ok(())
+        });
+
+        return notifications;
+    }
+
+    pub fn set_workspace_notification_enabled(
+        app_handle: &AppHandle,
+        event: WorkspaceEvent,
+        enabled: bool,
+    ) -> anyhow::Result<()> {
+        let mut notifications = Self::workspace_notifications(app_handle);
+        notifications.insert(event, enabled);
+        let value = serde_json::to_value(notifications)?;
+        with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            store.insert("workspaceNotifications".to_string(), value)?;
+
+            store.save()
+        })
+    }
+
+    pub fn set_environment_profile(
+        app_handle: &AppHandle,
+        environment_profile: &HashMap<String, String>,
+    ) -> anyhow::Result<()> {
+        let value = serde_json::to_value(environment_profile)?;
+        with_data_store(&app_handle, SETTINGS_FILE_NAME, |store| {
+            store.insert("environmentProfile".to_string(), value)?;
+
+            store.save()
+        })
+    }
 }
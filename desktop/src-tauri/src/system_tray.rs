@@ -1,10 +1,61 @@
-use crate::{workspaces::WorkspacesState, AppHandle, AppState, UiMessage};
+use crate::{
+    contexts::ContextsState,
+    machines::MachinesState,
+    workspaces::{WorkspacesState, WorkspacesSummary},
+    AppHandle, AppState, UiMessage,
+};
+use lazy_static::lazy_static;
 use log::{error, warn};
+use std::sync::Mutex;
 use tauri::{
-    CustomMenuItem, Manager, State, SystemTray as TauriSystemTray, SystemTrayEvent, SystemTrayMenu,
-    SystemTrayMenuItem, SystemTraySubmenu,
+    CustomMenuItem, Icon, Manager, State, SystemTray as TauriSystemTray, SystemTrayEvent,
+    SystemTrayMenu, SystemTrayMenuItem, SystemTraySubmenu,
 };
 
+const APP_NAME: &str = "DevPod";
+
+lazy_static! {
+    static ref CURRENT_ICON: Mutex<TrayIcon> = Mutex::new(TrayIcon::Idle);
+}
+
+/// `TrayIcon` is the icon variant for the aggregate workspace state.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum TrayIcon {
+    Idle,
+    Busy,
+    Error,
+}
+
+impl TrayIcon {
+    fn from_summary(summary: &WorkspacesSummary) -> Self {
+        if summary.failed > 0 || summary.refresh_failed {
+            TrayIcon::Error
+        } else if summary.busy > 0 {
+            TrayIcon::Busy
+        } else {
+            TrayIcon::Idle
+        }
+    }
+
+    fn icon(&self) -> Icon {
+        let bytes: &[u8] = match self {
+            TrayIcon::Idle => include_bytes!("../icons/icon.ico"),
+            TrayIcon::Busy => include_bytes!("../icons/tray/busy.ico"),
+            TrayIcon::Error => include_bytes!("../icons/tray/error.ico"),
+        };
+
+        Icon::Raw(bytes.to_vec())
+    }
+}
+
+fn tooltip(summary: &WorkspacesSummary) -> String {
+    if summary.refresh_failed {
+        format!("{}: {} (unable to refresh)", APP_NAME, summary)
+    } else {
+        format!("{}: {}", APP_NAME, summary)
+    }
+}
+
 pub trait SystemTrayIdentifier {}
 pub type SystemTrayClickHandler = Box<dyn Fn(&AppHandle, State<AppState>)>;
 pub trait ToSystemTraySubmenu {
@@ -23,17 +74,22 @@ impl SystemTray {
 impl SystemTray {
     const QUIT_ID: &str = "quit";
     const SHOW_DASHBOARD_ID: &str = "show_dashboard";
+    const SUMMARY_ID: &str = "summary";
 }
 
 impl SystemTray {
     pub fn build_menu(
         &self,
+        summary: &WorkspacesSummary,
         submenu_builders: Vec<Box<&dyn ToSystemTraySubmenu>>,
     ) -> SystemTrayMenu {
+        let summary = CustomMenuItem::new(Self::SUMMARY_ID, summary.to_string()).disabled();
         let show_dashboard = CustomMenuItem::new(Self::SHOW_DASHBOARD_ID, "Show Dashboard");
         let quit = CustomMenuItem::new(Self::QUIT_ID, "Quit");
 
         let mut tray_menu = SystemTrayMenu::new()
+            .add_item(summary)
+            .add_native_item(SystemTrayMenuItem::Separator)
             .add_item(show_dashboard)
             .add_native_item(SystemTrayMenuItem::Separator);
 
@@ -49,13 +105,58 @@ impl SystemTray {
         tray_menu
     }
 
+    /// `rebuild_menu` replaces the current tray menu with one built from the latest app state
+    /// and updates the icon and tooltip to match the aggregate workspace state.
+    /// None of the state locks may be held by the caller.
+    pub fn rebuild_menu(&self, app_handle: &AppHandle) {
+        let state = app_handle.state::<AppState>();
+        let workspaces = state.workspaces.lock().unwrap();
+        let summary = workspaces.summary(&state.operations.lock().unwrap());
+        let machines = state.machines.lock().unwrap();
+        let contexts = state.contexts.lock().unwrap();
+
+        let new_menu = self.build_menu(
+            &summary,
+            vec![
+                Box::new(&*workspaces),
+                Box::new(&*machines),
+                Box::new(&*contexts),
+            ],
+        );
+        let tray_handle = app_handle.tray_handle();
+        if let Err(err) = tray_handle.set_menu(new_menu) {
+            error!("Failed to set tray menu: {}", err);
+        }
+        if let Err(err) = tray_handle.set_tooltip(&tooltip(&summary)) {
+            error!("Failed to set tray tooltip: {}", err);
+        }
+
+        let icon = TrayIcon::from_summary(&summary);
+        let mut current_icon = CURRENT_ICON.lock().unwrap();
+        if *current_icon != icon {
+            if let Err(err) = tray_handle.set_icon(icon.icon()) {
+                error!("Failed to set tray icon: {}", err);
+                return;
+            }
+            // template icons are rendered monochrome, the badges need their colors
+            #[cfg(target_os = "macos")]
+            if let Err(err) = tray_handle.set_icon_as_template(icon == TrayIcon::Idle) {
+                error!("Failed to set tray icon as template: {}", err);
+            }
+            *current_icon = icon;
+        }
+    }
+
     pub fn build_tray(
         &self,
         submenu_builders: Vec<Box<&dyn ToSystemTraySubmenu>>,
     ) -> TauriSystemTray {
-        let tray_menu = self.build_menu(submenu_builders);
+        let summary = WorkspacesSummary::default();
+        let tray_menu = self.build_menu(&summary, submenu_builders);
 
-        TauriSystemTray::new().with_menu(tray_menu)
+        TauriSystemTray::new()
+            .with_menu(tray_menu)
+            .with_tooltip(&tooltip(&summary))
     }
 
     pub fn get_event_handler(&self) -> impl Fn(&AppHandle, SystemTrayEvent) + Send + Sync {
@@ -81,6 +182,12 @@ impl SystemTray {
                     if id.starts_with(WorkspacesState::IDENTIFIER_PREFIX) {
                         let workspaces_state = &*app_state.workspaces.lock().unwrap();
                         maybe_handler = workspaces_state.on_tray_item_clicked(id);
+                    } else if id.starts_with(MachinesState::IDENTIFIER_PREFIX) {
+                        let machines_state = &*app_state.machines.lock().unwrap();
+                        maybe_handler = machines_state.on_tray_item_clicked(id);
+                    } else if id.starts_with(ContextsState::IDENTIFIER_PREFIX) {
+                        let contexts_state = &*app_state.contexts.lock().unwrap();
+                        maybe_handler = contexts_state.on_tray_item_clicked(id);
                     } else {
                         warn!("Received unhandled click for ID: {}", id);
                     }
@@ -0,0 +1,330 @@
+use crate::{settings::Settings, system_tray::SystemTray, workspaces::WorkspaceKey, AppHandle};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::BTreeMap, sync::RwLock};
+use ts_rs::TS;
+
+lazy_static! {
+    static ref TRAY_OPTIONS: RwLock<TrayOptions> = RwLock::new(TrayOptions::default());
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum TrayGrouping {
+    #[default]
+    None,
+    Provider,
+    Context,
+    Repository,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum TraySorting {
+    #[default]
+    Name,
+    Created,
+    Recent,
+}
+
+/// `TrayOptions` controls how workspaces are laid out in the tray menu.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct TrayOptions {
+    #[serde(default)]
+    pub grouping: TrayGrouping,
+    #[serde(default)]
+    pub sorting: TraySorting,
+    /// The workspaces pinned to the top of the menu
+    #[serde(default)]
+    pub favorites: Vec<WorkspaceKey>,
+    /// Only the most recently used workspaces are shown if set, favorites don't count
+    pub recent_limit: Option<usize>,
+}
+
+impl TrayOptions {
+    pub fn is_favorite(&self, key: &WorkspaceKey) -> bool {
+        self.favorites.contains(key)
+    }
+
+    /// `toggle_favorite` pins the workspace identified by `key` or unpins it if it already is.
+    pub fn toggle_favorite(&mut self, key: &WorkspaceKey) {
+        if self.is_favorite(key) {
+            self.favorites.retain(|favorite| favorite != key);
+        } else {
+            self.favorites.push(key.clone());
+        }
+    }
+}
+
+pub fn tray_options() -> TrayOptions {
+    TRAY_OPTIONS.read().unwrap().clone()
+}
+
+pub fn set_tray_options(options: TrayOptions) {
+    *TRAY_OPTIONS.write().unwrap() = options;
+}
+
+pub fn setup(app_handle: &AppHandle) {
+    set_tray_options(Settings::tray_options(app_handle));
+}
+
+/// `toggle_favorite` pins or unpins the workspace identified by `key`, persists the change and rebuilds the tray menu.
+pub fn toggle_favorite(app_handle: &AppHandle, key: &WorkspaceKey) {
+    let options = {
+        let mut options = TRAY_OPTIONS.write().unwrap();
+        options.toggle_favorite(key);
+        options.clone()
+    };
+    if let Err(err) = Settings::set_tray_options(app_handle, &options) {
+        error!("Failed to persist tray options: {}", err);
+    }
+
+    SystemTray::new().rebuild_menu(app_handle);
+}
+
+/// Replaces the tray options and rebuilds the tray menu.
+#[tauri::command]
+pub fn update_tray_options(app_handle: AppHandle, options: TrayOptions) {
+    if let Err(err) = Settings::set_tray_options(&app_handle, &options) {
+        error!("Failed to persist tray options: {}", err);
+    }
+    set_tray_options(options);
+
+    SystemTray::new().rebuild_menu(&app_handle);
+}
+
+/// `TrayEntry` is anything that can be laid out in the tray.
+pub trait TrayEntry {
+    fn entry_key(&self) -> Option<WorkspaceKey>;
+    fn group(&self, grouping: TrayGrouping) -> Option<String>;
+    fn created(&self) -> Option<DateTime<Utc>>;
+    fn last_used(&self) -> Option<DateTime<Utc>>;
+}
+
+#[derive(Debug)]
+pub struct TrayGroup<'a, T> {
+    /// `None` for entries that don't belong to any group
+    pub name: Option<String>,
+    pub entries: Vec<&'a T>,
+}
+
+#[derive(Debug)]
+pub struct TrayLayout<'a, T> {
+    pub favorites: Vec<&'a T>,
+    pub groups: Vec<TrayGroup<'a, T>>,
+    /// Number of entries left out because of the `recent_limit`
+    pub hidden: usize,
+}
+
+/// `layout` pins favorites, applies the recent limit and groups and sorts the remaining entries.
+pub fn layout<'a, T: TrayEntry>(entries: &'a [T], options: &TrayOptions) -> TrayLayout<'a, T> {
+    let entries: Vec<(WorkspaceKey, &T)> = entries
+        .iter()
+        .filter_map(|entry| entry.entry_key().map(|key| (key, entry)))
+        .collect();
+
+    let favorites: Vec<&T> = options
+        .favorites
+        .iter()
+        .filter_map(|favorite| {
+            entries
+                .iter()
+                .find(|(key, _)| key == favorite)
+                .map(|(_, entry)| *entry)
+        })
+        .collect();
+
+    let mut rest: Vec<&T> = entries
+        .into_iter()
+        .filter(|(key, _)| !options.is_favorite(key))
+        .map(|(_, entry)| entry)
+        .collect();
+    let mut hidden = 0;
+    if let Some(limit) = options.recent_limit {
+        rest.sort_by(|a, b| compare(*a, *b, TraySorting::Recent));
+        hidden = rest.len().saturating_sub(limit);
+        rest.truncate(limit);
+    }
+    rest.sort_by(|a, b| compare(*a, *b, options.sorting));
+
+    // BTreeMap sorts the groups by name, entries without a group go last
+    let mut grouped: BTreeMap<String, Vec<&T>> = BTreeMap::new();
+    let mut ungrouped = vec![];
+    for entry in rest {
+        match entry.group(options.grouping) {
+            Some(group) => grouped.entry(group).or_default().push(entry),
+            None => ungrouped.push(entry),
+        }
+    }
+    let mut groups: Vec<TrayGroup<T>> = grouped
+        .into_iter()
+        .map(|(name, entries)| TrayGroup {
+            name: Some(name),
+            entries,
+        })
+        .collect();
+    if !ungrouped.is_empty() {
+        groups.push(TrayGroup {
+            name: None,
+            entries: ungrouped,
+        });
+    }
+
+    TrayLayout {
+        favorites,
+        groups,
+        hidden,
+    }
+}
+
+/// `compare` orders by name ascending, or newest first for the time based orders. Ties are broken by name.
+fn compare<T: TrayEntry>(a: &T, b: &T, sorting: TraySorting) -> Ordering {
+    let by_name = a.entry_key().cmp(&b.entry_key());
+    // `None` sorts before `Some`, so reversing puts entries without a timestamp last
+    let ordering = match sorting {
+        TraySorting::Name => Ordering::Equal,
+        TraySorting::Created => b.created().cmp(&a.created()),
+        TraySorting::Recent => b.last_used().cmp(&a.last_used()),
+    };
+
+    ordering.then(by_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    struct Entry {
+        id: Option<WorkspaceKey>,
+        provider: Option<String>,
+        created: i64,
+        last_used: Option<i64>,
+    }
+
+    impl TrayEntry for Entry {
+        fn entry_key(&self) -> Option<WorkspaceKey> {
+            self.id.clone()
+        }
+
+        fn group(&self, grouping: TrayGrouping) -> Option<String> {
+            match grouping {
+                TrayGrouping::Provider => self.provider.clone(),
+                _ => None,
+            }
+        }
+
+        fn created(&self) -> Option<DateTime<Utc>> {
+            Utc.timestamp_opt(self.created, 0).single()
+        }
+
+        fn last_used(&self) -> Option<DateTime<Utc>> {
+            self.last_used
+                .and_then(|last_used| Utc.timestamp_opt(last_used, 0).single())
+        }
+    }
+
+    fn key(id: &str) -> WorkspaceKey {
+        WorkspaceKey::new(id, None)
+    }
+
+    fn entry(id: &str, provider: Option<&str>, created: i64, last_used: Option<i64>) -> Entry {
+        Entry {
+            id: Some(key(id)),
+            provider: provider.map(String::from),
+            created,
+            last_used,
+        }
+    }
+
+    fn ids<T: TrayEntry>(entries: &[&T]) -> Vec<String> {
+        entries
+            .iter()
+            .filter_map(|entry| entry.entry_key().map(|key| key.id))
+            .collect()
+    }
+
+    fn entries() -> Vec<Entry> {
+        vec![
+            entry("c", Some("docker"), 1, Some(30)),
+            entry("a", Some("kubernetes"), 3, None),
+            entry("b", None, 2, Some(20)),
+            entry("d", Some("docker"), 4, Some(10)),
+        ]
+    }
+
+    #[test]
+    fn should_sort_entries() {
+        let entries = entries();
+
+        let layout = layout(&entries, &TrayOptions::default());
+        assert_eq!(layout.groups.len(), 1);
+        assert_eq!(ids(&layout.groups[0].entries), vec!["a", "b", "c", "d"]);
+
+        let options = TrayOptions {
+            sorting: TraySorting::Created,
+            ..Default::default()
+        };
+        let layout = super::layout(&entries, &options);
+        assert_eq!(ids(&layout.groups[0].entries), vec!["d", "a", "b", "c"]);
+
+        let options = TrayOptions {
+            sorting: TraySorting::Recent,
+            ..Default::default()
+        };
+        let layout = super::layout(&entries, &options);
+        assert_eq!(ids(&layout.groups[0].entries), vec!["c", "b", "d", "a"]);
+    }
+
+    #[test]
+    fn should_group_entries() {
+        let entries = entries();
+        let options = TrayOptions {
+            grouping: TrayGrouping::Provider,
+            ..Default::default()
+        };
+
+        let layout = layout(&entries, &options);
+
+        let groups: Vec<(Option<String>, Vec<String>)> = layout
+            .groups
+            .iter()
+            .map(|group| (group.name.clone(), ids(&group.entries)))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (Some("docker".to_string()), vec!["c".into(), "d".into()]),
+                (Some("kubernetes".to_string()), vec!["a".into()]),
+                (None, vec!["b".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_pin_favorites_and_limit_recent_entries() {
+        let entries = entries();
+        let mut options = TrayOptions {
+            favorites: vec![key("d"), key("unknown")],
+            recent_limit: Some(2),
+            ..Default::default()
+        };
+
+        let layout = layout(&entries, &options);
+        assert_eq!(ids(&layout.favorites), vec!["d"]);
+        assert_eq!(ids(&layout.groups[0].entries), vec!["b", "c"]);
+        assert_eq!(layout.hidden, 1);
+
+        options.toggle_favorite(&key("d"));
+        assert!(!options.is_favorite(&key("d")));
+        options.toggle_favorite(&key("a"));
+        assert!(options.is_favorite(&key("a")));
+        assert!(!options.is_favorite(&WorkspaceKey::new("a", Some("staging".to_string()))));
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     custom_protocol::{OpenWorkspaceMsg, ParseError},
     window::WindowHelper,
+    workspaces::WorkspaceKey,
     AppHandle,
 };
 use log::warn;
@@ -74,6 +75,7 @@ pub enum UiMessage {
     ShowToast(ShowToastMsg),
     OpenWorkspace(OpenWorkspaceMsg),
     OpenWorkspaceFailed(ParseError),
+    ShowWorkspaceLogs(ShowWorkspaceLogsMsg),
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -83,6 +85,32 @@ pub struct ShowToastMsg {
     status: ToastStatus,
 }
 
+impl ShowToastMsg {
+    pub fn new(title: String, message: String, status: ToastStatus) -> Self {
+        Self {
+            title,
+            message,
+            status,
+        }
+    }
+}
+
+/// `ShowWorkspaceLogsMsg` asks the UI to show the log of the latest action on a workspace, without running anything.
+#[derive(Debug, Serialize, Clone)]
+pub struct ShowWorkspaceLogsMsg {
+    workspace_id: String,
+    context: Option<String>,
+}
+
+impl ShowWorkspaceLogsMsg {
+    pub fn new(key: WorkspaceKey) -> Self {
+        Self {
+            workspace_id: key.id,
+            context: key.context,
+        }
+    }
+}
+
 // WARN: Needs to match the UI's toast status
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "lowercase")]
@@ -3,16 +3,15 @@ use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager, Wry};
 use tauri_plugin_store::{with_store, Store, StoreCollection};
 
-/// `measure`  the duration it took a function to execute.
-#[allow(dead_code)]
-pub fn measure<F>(f: F) -> Duration
+/// `measure` returns the result of a function along with the duration it took to execute.
+pub fn measure<T, F>(f: F) -> (T, Duration)
 where
-    F: Fn(),
+    F: FnOnce() -> T,
 {
     let start = Instant::now();
-    f();
+    let result = f();
 
-    start.elapsed()
+    (result, start.elapsed())
 }
 
 pub fn with_data_store<T, F: FnOnce(&mut Store<Wry>) -> Result<T, tauri_plugin_store::Error>>(
@@ -0,0 +1,308 @@
+use crate::{
+    settings::Settings,
+    workspaces::{WorkspaceKey, WorkspaceStatus},
+    AppHandle, AppState,
+};
+use anyhow::Context;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    path::PathBuf,
+    time::Duration,
+};
+use tauri::{api::notification::Notification, Manager};
+use ts_rs::TS;
+
+const NOTIFICATIONS_DIR: &str = "workspace_notifications";
+/// Commands that finish quicker than this aren't worth a notification, the user is most likely still watching them.
+const LONG_RUNNING: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Hash, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub enum WorkspaceEvent {
+    UpFinished,
+    UpFailed,
+    BuildFinished,
+    BuildFailed,
+    /// A workspace that was running is stopped
+    Stopped,
+    /// A workspace that was running can't be found anymore
+    Missing,
+    /// A workspace is about to be stopped for being idle
+    IdleWarning,
+    /// A workspace was stopped for being idle
+    IdleStopped,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LongRunningCommand {
+    Up,
+    Build,
+}
+
+impl WorkspaceEvent {
+    /// `command_finished` is the event for a terminated command, `None` if it didn't run long enough to notify about it.
+    fn command_finished(
+        command: LongRunningCommand,
+        succeeded: bool,
+        elapsed: Duration,
+    ) -> Option<Self> {
+        if elapsed < LONG_RUNNING {
+            return None;
+        }
+
+        let event = match (command, succeeded) {
+            (LongRunningCommand::Up, true) => Self::UpFinished,
+            (LongRunningCommand::Up, false) => Self::UpFailed,
+            (LongRunningCommand::Build, true) => Self::BuildFinished,
+            (LongRunningCommand::Build, false) => Self::BuildFailed,
+        };
+
+        Some(event)
+    }
+
+    /// `status_changed` is the event for a workspace that went from `previous` to `current`, if there is one.
+    fn status_changed(previous: Option<WorkspaceStatus>, current: WorkspaceStatus) -> Option<Self> {
+        match (previous, current) {
+            (Some(WorkspaceStatus::Running), WorkspaceStatus::Stopped) => Some(Self::Stopped),
+            (Some(WorkspaceStatus::Running), WorkspaceStatus::NotFound) => Some(Self::Missing),
+            _ => None,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::UpFinished => "Workspace ready",
+            Self::UpFailed => "Workspace failed to start",
+            Self::BuildFinished => "Build finished",
+            Self::BuildFailed => "Build failed",
+            Self::Stopped => "Workspace stopped",
+            Self::Missing => "Workspace not found",
+            Self::IdleWarning => "Idle workspace",
+            Self::IdleStopped => "Idle workspace stopped",
+        }
+    }
+
+    fn body(&self, workspace_id: &str) -> String {
+        match self {
+            Self::UpFinished => format!("{} is up and running", workspace_id),
+            Self::UpFailed => format!(
+                "Starting {} failed, check the logs for details",
+                workspace_id
+            ),
+            Self::BuildFinished => format!("Building {} finished", workspace_id),
+            Self::BuildFailed => format!(
+                "Building {} failed, check the logs for details",
+                workspace_id
+            ),
+            Self::Stopped => format!("{} was running and is stopped now", workspace_id),
+            Self::Missing => format!("{} was running and can't be found anymore", workspace_id),
+            Self::IdleWarning => format!(
+                "{} hasn't been used for a while and will be stopped soon",
+                workspace_id
+            ),
+            Self::IdleStopped => format!("{} was stopped because it wasn't used", workspace_id),
+        }
+    }
+}
+
+/// Notifies about a terminated `up` or `build` of the workspace identified by `key` if it ran long enough.
+pub fn notify_command_finished(
+    app_handle: &AppHandle,
+    command: LongRunningCommand,
+    key: &WorkspaceKey,
+    succeeded: bool,
+    elapsed: Duration,
+) {
+    let event = match WorkspaceEvent::command_finished(command, succeeded, elapsed) {
+        Some(event) => event,
+        None => return,
+    };
+
+    // every invocation terminates exactly once, there is nothing to deduplicate
+    if let Err(err) = notify(app_handle, event, key) {
+        warn!("Failed to send workspace notification: {}", err);
+    }
+}
+
+/// Notifies about an upcoming or done idle stop of the workspace identified by `key`.
+pub fn notify_idle(app_handle: &AppHandle, event: WorkspaceEvent, key: &WorkspaceKey) {
+    if let Err(err) = notify(app_handle, event, key) {
+        warn!("Failed to send workspace notification: {}", err);
+    }
+}
+
+/// Notifies when a running workspace is found stopped or missing, once until it is running again.
+/// Changes caused by operations of the workspace itself are expected and don't notify.
+pub fn notify_status_changed(
+    app_handle: &AppHandle,
+    key: &WorkspaceKey,
+    previous: Option<WorkspaceStatus>,
+    current: WorkspaceStatus,
+) {
+    if current == WorkspaceStatus::Running {
+        if let Err(err) = forget(app_handle, key) {
+            warn!("Failed to reset workspace notifications: {}", err);
+        }
+        return;
+    }
+
+    let event = match WorkspaceEvent::status_changed(previous, current) {
+        Some(event) => event,
+        None => return,
+    };
+    let state = app_handle.state::<AppState>();
+    if state.operations.lock().unwrap().expects_stopped(key) {
+        return;
+    }
+
+    // one notification per outage, no matter whether the workspace is stopped or missing
+    if let Err(err) = notify_once(app_handle, event, key) {
+        warn!("Failed to send workspace notification: {}", err);
+    }
+}
+
+/// `notify_once` is `notify`, unless a notification was shown for the current outage of the workspace already.
+fn notify_once(
+    app_handle: &AppHandle,
+    event: WorkspaceEvent,
+    key: &WorkspaceKey,
+) -> anyhow::Result<()> {
+    if !Settings::workspace_notification_enabled(app_handle, event) {
+        return Ok(());
+    }
+
+    let target = marker_path(get_notifications_dir(app_handle)?, key);
+    if target.exists() {
+        return Ok(());
+    }
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let _ = File::create(target)?;
+
+    notify(app_handle, event, key)
+}
+
+/// `notify` shows the notification for `event` unless it is disabled in the settings.
+fn notify(app_handle: &AppHandle, event: WorkspaceEvent, key: &WorkspaceKey) -> anyhow::Result<()> {
+    if !Settings::workspace_notification_enabled(app_handle, event) {
+        return Ok(());
+    }
+
+    let identifier = app_handle.config().tauri.bundle.identifier.clone();
+    Notification::new(identifier)
+        .title(event.title())
+        .body(event.body(&key.to_string()))
+        .show()?;
+
+    Ok(())
+}
+
+/// `forget` removes the notification shown for the workspace, the next outage notifies again.
+fn forget(app_handle: &AppHandle, key: &WorkspaceKey) -> anyhow::Result<()> {
+    let target = marker_path(get_notifications_dir(app_handle)?, key);
+    if target.exists() {
+        fs::remove_file(target)?;
+    }
+
+    Ok(())
+}
+
+/// `marker_path` is the file that marks a notification as shown, workspaces of a context share a directory.
+fn marker_path(dir: PathBuf, key: &WorkspaceKey) -> PathBuf {
+    match &key.context {
+        Some(context) => dir.join(context).join(&key.id),
+        None => dir.join(&key.id),
+    }
+}
+
+fn get_notifications_dir(app_handle: &AppHandle) -> anyhow::Result<PathBuf> {
+    let mut dir_path = app_handle
+        .path_resolver()
+        .app_cache_dir()
+        .context("App cache dir not found")?;
+    dir_path.push(NOTIFICATIONS_DIR);
+
+    Ok(dir_path)
+}
+
+/// Turns the desktop notification for `event` on or off.
+#[tauri::command]
+pub fn set_workspace_notification_enabled(
+    app_handle: AppHandle,
+    event: WorkspaceEvent,
+    enabled: bool,
+) {
+    if let Err(err) = Settings::set_workspace_notification_enabled(&app_handle, event, enabled) {
+        error!("Failed to persist workspace notification setting: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_only_notify_about_long_running_commands() {
+        let long = LONG_RUNNING + Duration::from_secs(1);
+
+        assert_eq!(
+            WorkspaceEvent::command_finished(LongRunningCommand::Up, true, long),
+            Some(WorkspaceEvent::UpFinished)
+        );
+        assert_eq!(
+            WorkspaceEvent::command_finished(LongRunningCommand::Build, false, long),
+            Some(WorkspaceEvent::BuildFailed)
+        );
+        assert_eq!(
+            WorkspaceEvent::command_finished(LongRunningCommand::Up, false, Duration::from_secs(1)),
+            None
+        );
+    }
+
+    #[test]
+    fn should_keep_markers_per_context() {
+        let dir = PathBuf::from("notifications");
+
+        assert_eq!(
+            marker_path(dir.clone(), &WorkspaceKey::new("a", None)),
+            dir.join("a")
+        );
+        assert_ne!(
+            marker_path(
+                dir.clone(),
+                &WorkspaceKey::new("a", Some("default".to_string()))
+            ),
+            marker_path(dir, &WorkspaceKey::new("a", Some("staging".to_string())))
+        );
+    }
+
+    #[test]
+    fn should_only_notify_about_running_workspaces_going_away() {
+        assert_eq!(
+            WorkspaceEvent::status_changed(
+                Some(WorkspaceStatus::Running),
+                WorkspaceStatus::Stopped
+            ),
+            Some(WorkspaceEvent::Stopped)
+        );
+        assert_eq!(
+            WorkspaceEvent::status_changed(
+                Some(WorkspaceStatus::Running),
+                WorkspaceStatus::NotFound
+            ),
+            Some(WorkspaceEvent::Missing)
+        );
+        assert_eq!(
+            WorkspaceEvent::status_changed(None, WorkspaceStatus::Stopped),
+            None
+        );
+        assert_eq!(
+            WorkspaceEvent::status_changed(Some(WorkspaceStatus::Busy), WorkspaceStatus::Stopped),
+            None
+        );
+    }
+}
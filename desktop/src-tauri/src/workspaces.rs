@@ -1,58 +1,469 @@
 use crate::{
-    commands::{list_workspaces::ListWorkspacesCommand, DevpodCommandConfig, DevpodCommandError},
+    commands::{
+        build_workspace::{BuildWorkspaceArgs, BuildWorkspaceCommand},
+        delete_workspace::{DeleteWorkspaceArgs, DeleteWorkspaceCommand},
+        exec_blocking,
+        list_contexts::ListContextsCommand,
+        list_workspaces::ListWorkspacesCommand,
+        runner::{CommandRunner, SidecarRunner},
+        stop_workspace::StopWorkspaceCommand,
+        stream::CommandHandle,
+        up_workspace::{UpWorkspaceArgs, UpWorkspaceCommand},
+        workspace_status::WorkspaceStatusCommand,
+        DevpodCommandConfig, DevpodCommandError,
+    },
     custom_protocol::OpenWorkspaceMsg,
+    ides::{self, Ides},
+    invocation_history::Caller,
+    operations::{self, Operation, OperationKind, OperationScheduler},
+    refresher::Refresher,
     system_tray::{SystemTrayClickHandler, ToSystemTraySubmenu},
+    tray_layout::{self, TrayEntry, TrayGrouping},
+    workspace_notifications::{self, LongRunningCommand},
+};
+use crate::{
+    system_tray::SystemTray,
+    ui_messages::{ShowToastMsg, ShowWorkspaceLogsMsg, ToastStatus},
+    AppHandle, AppState, UiMessage,
 };
-use crate::{system_tray::SystemTray, AppHandle, AppState, UiMessage};
 use chrono::DateTime;
-use log::error;
+use log::{error, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::{
-    sync::{mpsc, Arc},
-    thread, time,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use tauri::{
+    ClipboardManager, CustomMenuItem, Manager, SystemTrayMenu, SystemTrayMenuItem,
+    SystemTraySubmenu, Window,
 };
-use tauri::{CustomMenuItem, SystemTrayMenu, SystemTrayMenuItem, SystemTraySubmenu};
 use tokio::sync::OnceCell;
+use ts_rs::TS;
 
 static INIT: OnceCell<()> = OnceCell::const_new();
 
+// WARN: needs to match the event name the UI listens to
+pub const WORKSPACES_CHANGED_EVENT: &str = "workspaces_changed";
+
+/// Number of failed polls in a row before we tell the user that the workspaces might be out of date.
+const FAILURES_BEFORE_NOTIFYING: u32 = 3;
+/// How often we check the status of workspaces that are busy or have operations queued.
+const ACTIVE_STATUS_INTERVAL: Duration = Duration::from_secs(5);
+/// How often we check the status of all other workspaces.
+const IDLE_STATUS_INTERVAL: Duration = Duration::from_secs(60);
+const STATUS_TICK: Duration = Duration::from_secs(1);
+/// Every status check spawns the CLI, don't run more of them at the same time.
+const MAX_CONCURRENT_STATUS_CHECKS: usize = 4;
+/// The IDE that starts a workspace without opening anything.
+const NO_IDE: &str = "none";
+
 enum Update {
     Workspaces(WorkspacesState),
+    Failed(String),
+    Status(WorkspaceKey, WorkspaceStatus),
+}
+
+/// `WorkspaceKey` identifies a workspace, IDs are only unique within a context.
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceKey {
+    pub id: String,
+    /// Only `None` if the contexts couldn't be listed
+    pub context: Option<String>,
+}
+
+impl WorkspaceKey {
+    pub fn new(id: impl Into<String>, context: Option<String>) -> Self {
+        WorkspaceKey {
+            id: id.into(),
+            context,
+        }
+    }
+}
+
+/// Shows the ID, followed by the context if there is one, e.g. "a (staging)".
+impl std::fmt::Display for WorkspaceKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.context {
+            Some(context) => write!(f, "{} ({})", self.id, context),
+            None => write!(f, "{}", self.id),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
 #[serde(
     transparent,
     rename_all(serialize = "camelCase", deserialize = "camelCase")
 )]
 pub struct WorkspacesState {
     workspaces: Vec<Workspace>,
+    /// Set while polling fails, the workspaces are the last ones we successfully loaded
+    #[serde(skip)]
+    refresh_error: Option<String>,
+    /// Errors of the contexts that failed to list by name, their workspaces are the last ones we successfully loaded
+    #[serde(skip)]
+    context_errors: BTreeMap<String, String>,
 }
 
 impl WorkspacesState {
     pub const IDENTIFIER_PREFIX: &str = "workspaces-";
     const CREATE_WORKSPACE_ID: &str = "workspaces-create_workspace";
-
-    fn item_id(id: &String) -> String {
-        format!("{}{}", Self::IDENTIFIER_PREFIX, id)
-    }
+    const REFRESH_ERROR_ID: &str = "workspaces-refresh_error";
+    const CONTEXT_ERROR_PREFIX: &str = "workspaces-context_error:";
+    const SHOW_ALL_ID: &str = "workspaces-show_all";
 }
 
 impl WorkspacesState {
     pub fn load() -> Result<Self, DevpodCommandError> {
+        Self::load_with(&SidecarRunner::new(Caller::App))
+    }
+
+    pub fn load_with(runner: &dyn CommandRunner) -> Result<Self, DevpodCommandError> {
         let list_workspaces_cmd = ListWorkspacesCommand::new();
 
-        list_workspaces_cmd.exec()
+        list_workspaces_cmd.exec_with(runner)
+    }
+
+    /// `load_all_with` lists the workspaces of every context and tags each of them with its context.
+    /// Contexts that fail to list keep their workspaces from `current` and report their error, unless all of them fail.
+    pub fn load_all_with(
+        runner: &dyn CommandRunner,
+        current: &WorkspacesState,
+    ) -> Result<Self, DevpodCommandError> {
+        let contexts = match ListContextsCommand::new().exec_with(runner) {
+            Ok(contexts) if !contexts.names().is_empty() => contexts.names(),
+            Ok(..) => return Self::load_with(runner),
+            Err(err) => {
+                warn!(
+                    "Failed to list contexts, only listing the active one: {}",
+                    err
+                );
+                return Self::load_with(runner);
+            }
+        };
+
+        let mut state = WorkspacesState::default();
+        let mut first_error = None;
+        let mut listed_any = false;
+        for context in contexts {
+            let result = ListWorkspacesCommand::with_context(context.clone())
+                .and_then(|cmd| cmd.exec_with(runner));
+            match result {
+                Ok(workspaces) => {
+                    listed_any = true;
+                    state
+                        .workspaces
+                        .extend(workspaces.workspaces.into_iter().map(|mut workspace| {
+                            workspace.context = Some(context.clone());
+                            workspace
+                        }));
+                }
+                Err(err) => {
+                    warn!("Failed to list workspaces of context {}: {}", context, err);
+                    state.workspaces.extend(
+                        current
+                            .workspaces
+                            .iter()
+                            .filter(|workspace| workspace.context.as_ref() == Some(&context))
+                            .cloned(),
+                    );
+                    state.context_errors.insert(context, err.to_string());
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+
+        match first_error {
+            Some(err) if !listed_any => Err(err),
+            _ => Ok(state),
+        }
+    }
+
+    pub fn workspaces(&self) -> &[Workspace] {
+        &self.workspaces
+    }
+
+    /// `filter` keeps the workspaces that match all criteria of `filter`.
+    fn filter(&self, filter: &WorkspaceFilter) -> Self {
+        WorkspacesState {
+            workspaces: self
+                .workspaces
+                .iter()
+                .filter(|workspace| filter.matches(workspace))
+                .cloned()
+                .collect(),
+            refresh_error: self.refresh_error.clone(),
+            context_errors: self.context_errors.clone(),
+        }
+    }
+
+    /// `is_stale` tells whether some of the workspaces might be out of date because polling them failed.
+    fn is_stale(&self) -> bool {
+        self.refresh_error.is_some() || !self.context_errors.is_empty()
+    }
+
+    /// `matching` returns the keys of all workspaces `id`, in any context unless `context` is set.
+    pub fn matching(&self, id: &str, context: Option<&str>) -> Vec<WorkspaceKey> {
+        self.workspaces
+            .iter()
+            .filter(|workspace| {
+                workspace.id.as_deref() == Some(id)
+                    && context
+                        .iter()
+                        .all(|context| workspace.context.as_deref() == Some(*context))
+            })
+            .filter_map(Workspace::key)
+            .collect()
+    }
+
+    /// `find` looks up workspace `id` in `context`, workspace IDs are only unique within a context.
+    fn find(&self, id: &str, context: Option<&str>) -> Option<&Workspace> {
+        self.workspaces.iter().find(|workspace| {
+            workspace.id.as_deref() == Some(id) && workspace.context.as_deref() == context
+        })
+    }
+
+    /// `merge_statuses` keeps the statuses we already know for workspaces that are still around, `devpod list` doesn't report them.
+    fn merge_statuses(&mut self, current: &WorkspacesState) {
+        for workspace in &mut self.workspaces {
+            if workspace.status.is_some() {
+                continue;
+            }
+            workspace.status = current
+                .workspaces
+                .iter()
+                .find(|current_workspace| current_workspace.is(workspace))
+                .and_then(|current_workspace| current_workspace.status);
+        }
+    }
+
+    /// `summary` counts the workspaces by state, workspaces with pending operations count as busy.
+    pub fn summary(&self, operations: &OperationScheduler) -> WorkspacesSummary {
+        let mut summary = WorkspacesSummary {
+            refresh_failed: self.is_stale(),
+            ..Default::default()
+        };
+        for workspace in &self.workspaces {
+            let key = workspace.key();
+            if key.iter().any(|key| operations.is_busy(key)) {
+                summary.busy += 1;
+            } else if key.iter().any(|key| operations.has_failed(key)) {
+                summary.failed += 1;
+            } else {
+                match workspace.status {
+                    Some(WorkspaceStatus::Running) => summary.running += 1,
+                    Some(WorkspaceStatus::Busy) => summary.busy += 1,
+                    Some(WorkspaceStatus::Stopped) | Some(WorkspaceStatus::NotFound) | None => {
+                        summary.stopped += 1
+                    }
+                }
+            }
+        }
+
+        summary
+    }
+
+    fn status(&self, key: &WorkspaceKey) -> Option<WorkspaceStatus> {
+        self.workspaces
+            .iter()
+            .find(|workspace| workspace.has_key(key))
+            .and_then(|workspace| workspace.status)
+    }
+
+    /// `set_status` updates the status of the workspace identified by `key`, returns whether it changed.
+    fn set_status(&mut self, key: &WorkspaceKey, status: WorkspaceStatus) -> bool {
+        let workspace = self
+            .workspaces
+            .iter_mut()
+            .find(|workspace| workspace.has_key(key));
+
+        match workspace {
+            Some(workspace) if workspace.status != Some(status) => {
+                workspace.status = Some(status);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl WorkspacesState {
+    /// `machine_usage` maps machine IDs to the keys of all workspaces running on them.
+    pub fn machine_usage(&self) -> HashMap<String, Vec<WorkspaceKey>> {
+        let mut usage: HashMap<String, Vec<WorkspaceKey>> = HashMap::new();
+        for workspace in &self.workspaces {
+            if let (Some(key), Some(machine_id)) = (workspace.key(), workspace.machine_id()) {
+                usage.entry(machine_id.clone()).or_default().push(key);
+            }
+        }
+
+        usage
     }
 }
 
-impl WorkspacesState {}
+/// `WorkspaceAction` is what the tray can do with a single workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WorkspaceAction {
+    /// Opens the workspace in the given IDE, or the one it was created with
+    Open(Option<String>),
+    Start,
+    Stop,
+    Rebuild,
+    Delete,
+    CopySshCommand,
+    ViewLogs,
+    /// Pins the workspace to the top of the tray menu, or unpins it
+    TogglePin,
+}
+
+impl WorkspaceAction {
+    const OPEN_IDE_PREFIX: &str = "open_";
+
+    fn id(&self) -> String {
+        match self {
+            Self::Open(None) => "open".to_string(),
+            Self::Open(Some(ide)) => format!("{}{}", Self::OPEN_IDE_PREFIX, ide),
+            Self::Start => "start".to_string(),
+            Self::Stop => "stop".to_string(),
+            Self::Rebuild => "rebuild".to_string(),
+            Self::Delete => "delete".to_string(),
+            Self::CopySshCommand => "copy_ssh".to_string(),
+            Self::ViewLogs => "logs".to_string(),
+            Self::TogglePin => "pin".to_string(),
+        }
+    }
+
+    fn parse(action: &str) -> Option<Self> {
+        match action {
+            "open" => Some(Self::Open(None)),
+            "start" => Some(Self::Start),
+            "stop" => Some(Self::Stop),
+            "rebuild" => Some(Self::Rebuild),
+            "delete" => Some(Self::Delete),
+            "copy_ssh" => Some(Self::CopySshCommand),
+            "logs" => Some(Self::ViewLogs),
+            "pin" => Some(Self::TogglePin),
+            action => action
+                .strip_prefix(Self::OPEN_IDE_PREFIX)
+                .filter(|ide| !ide.is_empty())
+                .map(|ide| Self::Open(Some(ide.to_string()))),
+        }
+    }
+}
+
+impl WorkspacesState {
+    /// `action_item_id` identifies `action` on a workspace as `<prefix><action>:[<context>/]<workspace>`.
+    fn action_item_id(
+        action: &WorkspaceAction,
+        workspace_id: &str,
+        context: Option<&str>,
+    ) -> String {
+        match context {
+            Some(context) => format!(
+                "{}{}:{}/{}",
+                Self::IDENTIFIER_PREFIX,
+                action.id(),
+                context,
+                workspace_id
+            ),
+            None => format!(
+                "{}{}:{}",
+                Self::IDENTIFIER_PREFIX,
+                action.id(),
+                workspace_id
+            ),
+        }
+    }
+
+    /// `parse_action_item_id` is the reverse of `action_item_id`.
+    fn parse_action_item_id(id: &str) -> Option<(WorkspaceAction, &str, Option<&str>)> {
+        let (action, target) = id
+            .strip_prefix(Self::IDENTIFIER_PREFIX)
+            .and_then(|id| id.split_once(':'))?;
+        let action = WorkspaceAction::parse(action)?;
+
+        match target.split_once('/') {
+            Some((context, workspace_id)) => Some((action, workspace_id, Some(context))),
+            None => Some((action, target, None)),
+        }
+    }
+
+    fn workspace_submenu(
+        workspace: &Workspace,
+        title: &str,
+        is_favorite: bool,
+        ides: &Ides,
+    ) -> SystemTraySubmenu {
+        let workspace_id = workspace.id.as_deref().unwrap_or_default();
+        let context = workspace.context.as_deref();
+        let action_item = |action: WorkspaceAction, title: &str| {
+            CustomMenuItem::new(Self::action_item_id(&action, workspace_id, context), title)
+        };
+
+        let mut ides_menu = SystemTrayMenu::new();
+        for ide in ides {
+            if let (Some(name), Some(display_name)) = (ide.name(), ide.display_name()) {
+                ides_menu = ides_menu.add_item(action_item(
+                    WorkspaceAction::Open(Some(name.clone())),
+                    display_name,
+                ));
+            }
+        }
+
+        let mut menu =
+            SystemTrayMenu::new().add_item(action_item(WorkspaceAction::Open(None), "Open"));
+        if !ides.is_empty() {
+            menu = menu.add_submenu(SystemTraySubmenu::new("Open in", ides_menu));
+        }
+
+        menu = menu
+            .add_native_item(SystemTrayMenuItem::Separator)
+            .add_item(action_item(WorkspaceAction::Start, "Start"))
+            .add_item(action_item(WorkspaceAction::Stop, "Stop"))
+            .add_item(action_item(WorkspaceAction::Rebuild, "Rebuild"))
+            .add_item(action_item(WorkspaceAction::Delete, "Delete"))
+            .add_native_item(SystemTrayMenuItem::Separator)
+            .add_item(action_item(
+                WorkspaceAction::CopySshCommand,
+                "Copy SSH Command",
+            ))
+            .add_item(action_item(WorkspaceAction::ViewLogs, "View Logs"))
+            .add_item(action_item(
+                WorkspaceAction::TogglePin,
+                if is_favorite { "Unpin" } else { "Pin" },
+            ));
+
+        SystemTraySubmenu::new(title, menu)
+    }
+}
 
 impl ToSystemTraySubmenu for WorkspacesState {
     fn to_submenu(&self) -> tauri::SystemTraySubmenu {
         let mut workspaces_menu = SystemTrayMenu::new();
 
+        if self.refresh_error.is_some() {
+            workspaces_menu = workspaces_menu.add_item(
+                CustomMenuItem::new(Self::REFRESH_ERROR_ID, "⚠ Unable to refresh workspaces")
+                    .disabled(),
+            );
+        }
+        for context in self.context_errors.keys() {
+            workspaces_menu = workspaces_menu.add_item(
+                CustomMenuItem::new(
+                    format!("{}{}", Self::CONTEXT_ERROR_PREFIX, context),
+                    format!("⚠ Unable to refresh context {}", context),
+                )
+                .disabled(),
+            );
+        }
+        if self.is_stale() {
+            workspaces_menu = workspaces_menu.add_native_item(SystemTrayMenuItem::Separator);
+        }
+
         workspaces_menu = workspaces_menu.add_item(CustomMenuItem::new(
             Self::CREATE_WORKSPACE_ID,
             "Create Workspace",
@@ -62,81 +473,474 @@ impl ToSystemTraySubmenu for WorkspacesState {
             workspaces_menu = workspaces_menu.add_native_item(SystemTrayMenuItem::Separator);
         }
 
-        for workspace in &self.workspaces {
-            if let Some(id) = workspace.id() {
-                let item = CustomMenuItem::new(Self::item_id(id), id);
-                workspaces_menu = workspaces_menu.add_item(item);
+        let ides = ides::catalog();
+        let options = tray_layout::tray_options();
+        let layout = tray_layout::layout(&self.workspaces, &options);
+        // tag workspaces with their context once there is more than one
+        let show_context = self
+            .workspaces
+            .iter()
+            .any(|workspace| workspace.context != self.workspaces[0].context);
+        let title = |workspace: &Workspace| {
+            let id = workspace.id.as_deref().unwrap_or_default();
+            match &workspace.context {
+                Some(context) if show_context => format!("{} ({})", id, context),
+                _ => id.to_string(),
             }
+        };
+
+        for workspace in &layout.favorites {
+            workspaces_menu = workspaces_menu.add_submenu(Self::workspace_submenu(
+                workspace,
+                &format!("★ {}", title(workspace)),
+                true,
+                &ides,
+            ));
+        }
+        if !layout.favorites.is_empty() && !layout.groups.is_empty() {
+            workspaces_menu = workspaces_menu.add_native_item(SystemTrayMenuItem::Separator);
         }
 
-        SystemTraySubmenu::new("Workspaces", workspaces_menu)
-    }
+        for group in &layout.groups {
+            let submenus = group.entries.iter().map(|workspace| {
+                Self::workspace_submenu(workspace, &title(workspace), false, &ides)
+            });
 
-    fn on_tray_item_clicked(&self, id: &str) -> Option<SystemTrayClickHandler> {
-        let id = id.clone().to_string();
+            // without grouping, workspaces go right into the menu
+            if options.grouping == TrayGrouping::None {
+                for submenu in submenus {
+                    workspaces_menu = workspaces_menu.add_submenu(submenu);
+                }
+            } else {
+                let group_menu = submenus.fold(SystemTrayMenu::new(), |menu, submenu| {
+                    menu.add_submenu(submenu)
+                });
+                let name = group.name.as_deref().unwrap_or("Other");
+                workspaces_menu =
+                    workspaces_menu.add_submenu(SystemTraySubmenu::new(name, group_menu));
+            }
+        }
 
-        Some(Box::new(move |_app_handle, state| {
-            tauri::async_runtime::block_on(async {
-                let tx = &state.ui_messages;
+        if layout.hidden > 0 {
+            workspaces_menu = workspaces_menu.add_item(CustomMenuItem::new(
+                Self::SHOW_ALL_ID,
+                format!("{} More in Dashboard…", layout.hidden),
+            ));
+        }
 
-                if id == Self::CREATE_WORKSPACE_ID {
-                    if let Err(err) = tx
-                        .send(UiMessage::OpenWorkspace(OpenWorkspaceMsg::empty()))
-                        .await
-                    {
-                        error!("Failed to send create workspace message: {:?}", err);
+        let title = if self.is_stale() {
+            "Workspaces ⚠"
+        } else {
+            "Workspaces"
+        };
+
+        SystemTraySubmenu::new(title, workspaces_menu)
+    }
+
+    fn on_tray_item_clicked(&self, id: &str) -> Option<SystemTrayClickHandler> {
+        if id == Self::SHOW_ALL_ID {
+            return Some(Box::new(|_app_handle, state| {
+                tauri::async_runtime::block_on(async {
+                    if let Err(err) = state.ui_messages.send(UiMessage::ShowDashboard).await {
+                        error!("Failed to broadcast show dashboard message: {}", err);
                     };
-                } else {
-                    let workspace_id = id.replace(Self::IDENTIFIER_PREFIX, "");
-                    if let Err(err) = tx
-                        .send(UiMessage::OpenWorkspace(OpenWorkspaceMsg::with_id(
-                            workspace_id,
-                        )))
+                })
+            }));
+        }
+        if id == Self::CREATE_WORKSPACE_ID {
+            return Some(Box::new(|_app_handle, state| {
+                tauri::async_runtime::block_on(async {
+                    if let Err(err) = state
+                        .ui_messages
+                        .send(UiMessage::OpenWorkspace(OpenWorkspaceMsg::empty()))
                         .await
                     {
                         error!("Failed to send create workspace message: {:?}", err);
                     };
-                }
-            })
+                })
+            }));
+        }
+
+        let (action, workspace_id, context) = Self::parse_action_item_id(id)?;
+        let key = self.find(workspace_id, context)?.key()?;
+
+        Some(Box::new(move |app_handle, state| {
+            if let Err(err) = run_tray_action(app_handle, &state, &action, &key) {
+                error!("Failed to run {:?} on workspace {}: {}", action, key, err);
+            }
         }))
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+/// `run_tray_action` runs `action` through the same commands and operation queue the UI uses.
+fn run_tray_action(
+    app_handle: &AppHandle,
+    state: &AppState,
+    action: &WorkspaceAction,
+    key: &WorkspaceKey,
+) -> Result<(), DevpodCommandError> {
+    let up = |ide: Option<String>, recreate: bool| -> Result<(), DevpodCommandError> {
+        let cmd = UpWorkspaceCommand::new(UpWorkspaceArgs {
+            id: key.id.clone(),
+            context: key.context.clone(),
+            ide,
+            recreate,
+            ..Default::default()
+        })?;
+        operations::schedule(app_handle, key, OperationKind::Up, move |app_handle| {
+            cmd.stream(app_handle, Caller::Tray)
+        })?;
+
+        Ok(())
+    };
+
+    match action {
+        WorkspaceAction::Open(ide) => up(ide.clone(), false),
+        WorkspaceAction::Start => {
+            start(app_handle, key, Caller::Tray)?;
+
+            Ok(())
+        }
+        WorkspaceAction::Rebuild => up(None, true),
+        WorkspaceAction::Stop => {
+            stop(app_handle, key, Caller::Tray)?;
+
+            Ok(())
+        }
+        WorkspaceAction::Delete => {
+            let cmd = DeleteWorkspaceCommand::new(DeleteWorkspaceArgs {
+                id: key.id.clone(),
+                context: key.context.clone(),
+                ..Default::default()
+            })?;
+            let app_handle = app_handle.clone();
+            let key = key.clone();
+
+            // the dialog blocks, so it can't be shown from the main thread
+            thread::spawn(move || {
+                let confirmed = tauri::api::dialog::blocking::confirm(
+                    None::<&Window>,
+                    "Delete workspace",
+                    format!("Do you want to delete workspace {}?", key),
+                );
+                if !confirmed {
+                    return;
+                }
+
+                if let Err(err) = operations::schedule(
+                    &app_handle,
+                    &key,
+                    OperationKind::Delete,
+                    move |app_handle| cmd.stream(app_handle, Caller::Tray),
+                ) {
+                    error!("Failed to delete workspace {}: {}", key, err);
+                }
+            });
+
+            Ok(())
+        }
+        WorkspaceAction::CopySshCommand => {
+            if let Err(err) = app_handle
+                .clipboard_manager()
+                .write_text(ssh_command(&key.id, key.context.as_deref()))
+            {
+                error!("Failed to copy SSH command: {}", err);
+            }
+
+            Ok(())
+        }
+        WorkspaceAction::ViewLogs => {
+            tauri::async_runtime::block_on(async {
+                if let Err(err) = state
+                    .ui_messages
+                    .send(UiMessage::ShowWorkspaceLogs(ShowWorkspaceLogsMsg::new(
+                        key.clone(),
+                    )))
+                    .await
+                {
+                    error!("Failed to send show workspace logs message: {:?}", err);
+                };
+            });
+
+            Ok(())
+        }
+        WorkspaceAction::TogglePin => {
+            tray_layout::toggle_favorite(app_handle, key);
+
+            Ok(())
+        }
+    }
+}
+
+/// `WorkspaceFilter` narrows down the workspaces of `get_workspaces`, unset criteria match every workspace.
+#[derive(Deserialize, Debug, Default, Clone, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceFilter {
+    pub context: Option<String>,
+    pub provider: Option<String>,
+    pub status: Option<WorkspaceStatus>,
+    /// Case insensitive part of the workspace ID
+    pub query: Option<String>,
+}
+
+impl WorkspaceFilter {
+    fn matches(&self, workspace: &Workspace) -> bool {
+        let equals = |criterion: &Option<String>, value: Option<&String>| {
+            criterion.is_none() || criterion.as_ref() == value
+        };
+        let query_matches = self.query.iter().all(|query| {
+            workspace
+                .id
+                .iter()
+                .any(|id| id.to_lowercase().contains(&query.to_lowercase()))
+        });
+
+        equals(&self.context, workspace.context.as_ref())
+            && equals(&self.provider, workspace.provider_name())
+            && self
+                .status
+                .iter()
+                .all(|status| workspace.status == Some(*status))
+            && query_matches
+    }
+}
+
+/// `WorkspacesSummary` is the aggregate state of all workspaces the tray shows at a glance.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct WorkspacesSummary {
+    pub running: usize,
+    pub busy: usize,
+    pub stopped: usize,
+    /// Workspaces whose last operation failed
+    pub failed: usize,
+    pub refresh_failed: bool,
+}
+
+impl WorkspacesSummary {
+    pub fn is_empty(&self) -> bool {
+        self.running + self.busy + self.stopped + self.failed == 0
+    }
+}
+
+/// Lists the non-zero counts, e.g. "3 running, 1 failed".
+impl std::fmt::Display for WorkspacesSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "No workspaces");
+        }
+
+        let counts = [
+            (self.running, "running"),
+            (self.busy, "busy"),
+            (self.stopped, "stopped"),
+            (self.failed, "failed"),
+        ];
+        let parts: Vec<String> = counts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, label)| format!("{} {}", count, label))
+            .collect();
+
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+/// `start` queues starting the workspace identified by `key` without opening it, on behalf of `caller`.
+pub fn start(
+    app_handle: &AppHandle,
+    key: &WorkspaceKey,
+    caller: Caller,
+) -> Result<Operation, DevpodCommandError> {
+    let cmd = UpWorkspaceCommand::new(UpWorkspaceArgs {
+        id: key.id.clone(),
+        context: key.context.clone(),
+        ide: Some(NO_IDE.to_string()),
+        ..Default::default()
+    })?;
+
+    operations::schedule(app_handle, key, OperationKind::Up, move |app_handle| {
+        cmd.stream(app_handle, caller)
+    })
+}
+
+/// `stop` queues stopping the workspace identified by `key` on behalf of `caller`.
+pub fn stop(
+    app_handle: &AppHandle,
+    key: &WorkspaceKey,
+    caller: Caller,
+) -> Result<Operation, DevpodCommandError> {
+    stop_and_then(app_handle, key, caller, |_, _| {})
+}
+
+/// `stop_and_then` is `stop`, `on_finished` is called with whether the workspace was stopped.
+pub fn stop_and_then<F>(
+    app_handle: &AppHandle,
+    key: &WorkspaceKey,
+    caller: Caller,
+    on_finished: F,
+) -> Result<Operation, DevpodCommandError>
+where
+    F: FnOnce(&AppHandle, bool) + Send + 'static,
+{
+    let cmd = StopWorkspaceCommand::new(key.id.clone(), key.context.clone(), false)?;
+
+    operations::schedule_and_then(
+        app_handle,
+        key,
+        OperationKind::Stop,
+        move |app_handle| cmd.stream(app_handle, caller),
+        on_finished,
+    )
+}
+
+/// `command_key` identifies the workspace a UI command runs on, commands without a context run in the active one.
+fn command_key(app_handle: &AppHandle, id: &str, context: Option<&String>) -> WorkspaceKey {
+    let context = match context {
+        Some(context) => Some(context.clone()),
+        None => {
+            let state = app_handle.state::<AppState>();
+            let active = state.contexts.lock().unwrap().active().cloned();
+            active
+        }
+    };
+
+    WorkspaceKey::new(id, context)
+}
+
+/// `ssh_command` connects to the workspace in `context` through the CLI, the SSH host it sets up doesn't know about contexts.
+fn ssh_command(workspace_id: &str, context: Option<&str>) -> String {
+    match context {
+        Some(context) => format!("devpod ssh {} --context {}", workspace_id, context),
+        None => format!("devpod ssh {}", workspace_id),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
 #[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
-struct Workspace {
+#[ts(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Workspace {
     id: Option<String>,
     folder: Option<String>,
     provider: Option<WorkspaceProvider>,
+    machine: Option<WorkspaceMachine>,
     #[serde(rename = "ide")]
     ide_config: Option<WorkspaceIDE>,
     source: Option<WorkspaceSource>,
     creation_timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    last_used: Option<chrono::DateTime<chrono::Utc>>,
     context: Option<String>,
+    /// The result of the last `devpod status`, not part of the CLI output
+    #[serde(default)]
+    status: Option<WorkspaceStatus>,
 }
 impl Workspace {
     pub fn id(&self) -> &Option<String> {
         &self.id
     }
+
+    pub fn context(&self) -> Option<&String> {
+        self.context.as_ref()
+    }
+
+    pub fn key(&self) -> Option<WorkspaceKey> {
+        self.id
+            .as_ref()
+            .map(|id| WorkspaceKey::new(id.clone(), self.context.clone()))
+    }
+
+    fn has_key(&self, key: &WorkspaceKey) -> bool {
+        self.id.as_ref() == Some(&key.id) && self.context == key.context
+    }
+
+    /// `is` tells whether `other` is the same workspace, possibly in a different state.
+    fn is(&self, other: &Workspace) -> bool {
+        self.id == other.id && self.context == other.context
+    }
+
+    pub fn status(&self) -> Option<WorkspaceStatus> {
+        self.status
+    }
+
+    /// When the CLI last used the workspace, e.g. for an SSH session.
+    pub fn last_used(&self) -> Option<DateTime<chrono::Utc>> {
+        self.last_used
+    }
+
+    fn provider_name(&self) -> Option<&String> {
+        self.provider
+            .as_ref()
+            .and_then(|provider| provider.name.as_ref())
+    }
+
+    fn git_repository(&self) -> Option<&String> {
+        self.source
+            .as_ref()
+            .and_then(|source| source.git_repository.as_ref())
+    }
+
+    pub fn machine_id(&self) -> Option<&String> {
+        self.machine
+            .as_ref()
+            .and_then(|machine| machine.machine_id.as_ref())
+    }
+}
+
+impl TrayEntry for Workspace {
+    fn entry_key(&self) -> Option<WorkspaceKey> {
+        self.key()
+    }
+
+    fn group(&self, grouping: TrayGrouping) -> Option<String> {
+        match grouping {
+            TrayGrouping::None => None,
+            TrayGrouping::Provider => self.provider_name().cloned(),
+            TrayGrouping::Context => self.context.clone(),
+            TrayGrouping::Repository => self.git_repository().cloned(),
+        }
+    }
+
+    fn created(&self) -> Option<DateTime<chrono::Utc>> {
+        self.creation_timestamp
+    }
+
+    fn last_used(&self) -> Option<DateTime<chrono::Utc>> {
+        self.last_used
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+#[ts(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceMachine {
+    machine_id: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
 #[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
-struct WorkspaceProvider {
+#[ts(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceProvider {
     name: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
 #[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
-struct WorkspaceIDE {
+#[ts(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceIDE {
     #[serde(rename = "ide")]
     id: Option<String>,
     options: Option<HashMap<String, String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
 #[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
-struct WorkspaceSource {
+#[ts(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceSource {
     git_repository: Option<String>,
     git_branch: Option<String>,
     git_commit: Option<String>,
@@ -144,6 +948,25 @@ struct WorkspaceSource {
     image: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, TS)]
+#[ts(export)]
+pub enum WorkspaceStatus {
+    Running,
+    Busy,
+    Stopped,
+    NotFound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, TS)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
+#[ts(export)]
+pub struct WorkspaceStatusResult {
+    pub id: Option<String>,
+    pub context: Option<String>,
+    pub provider: Option<String>,
+    pub state: Option<WorkspaceStatus>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all(serialize = "camelCase", deserialize = "camelCase"))]
 struct ProviderOption {
@@ -155,37 +978,110 @@ struct ProviderOption {
 pub fn setup(app_handle: &AppHandle, state: tauri::State<'_, AppState>) {
     tauri::async_runtime::block_on(async {
         INIT.get_or_init(|| async {
-            let sleep_duration = time::Duration::from_millis(1_000);
             let (tx, rx) = mpsc::channel::<Update>();
 
-            let workspaces_tx = tx;
+            let workspaces_tx = tx.clone();
+            let status_tx = tx;
+            let refresher = Refresher::from_settings(app_handle);
+            let schedule = Arc::new(Mutex::new(StatusSchedule::default()));
+
+            let workspaces_state = Arc::clone(&state.workspaces);
+            thread::spawn(move || {
+                refresher.run(|| {
+                    poll_workspaces(
+                        &SidecarRunner::new(Caller::Poller),
+                        &workspaces_state,
+                        &workspaces_tx,
+                    )
+                });
+            });
 
-            thread::spawn(move || loop {
-                let workspaces = WorkspacesState::load().unwrap();
-                workspaces_tx.send(Update::Workspaces(workspaces)).unwrap();
+            let workspaces_state = Arc::clone(&state.workspaces);
+            let operations = Arc::clone(&state.operations);
+            let status_schedule = Arc::clone(&schedule);
+            thread::spawn(move || {
+                loop {
+                    poll_statuses(
+                        &SidecarRunner::new(Caller::Poller),
+                        &status_schedule,
+                        &workspaces_state,
+                        &operations,
+                        &status_tx,
+                    );
 
-                thread::sleep(sleep_duration);
+                    thread::sleep(STATUS_TICK);
+                }
             });
 
             let workspaces_state = Arc::clone(&state.workspaces);
-            let tray_handle = app_handle.tray_handle();
+            let app_handle = app_handle.clone();
 
             // Handle updates from background threads.
             thread::spawn(move || {
+                let mut health = PollHealth::default();
                 while let Ok(msg) = rx.recv() {
                     match msg {
                         Update::Workspaces(workspaces) => {
-                            let current_workspaces = &mut *workspaces_state.lock().unwrap();
-
-                            if current_workspaces != &workspaces {
-                                *current_workspaces = workspaces;
-
-                                // rebuild menu
-                                let new_menu = SystemTray::new()
-                                    .build_menu(vec![Box::new(current_workspaces)]);
-                                tray_handle
-                                    .set_menu(new_menu)
-                                    .expect("should be able to set menu");
+                            if health.recovered() {
+                                notify_poll_health(
+                                    &app_handle,
+                                    ShowToastMsg::new(
+                                        "Workspaces are up to date again".to_string(),
+                                        "DevPod is able to load your workspaces again".to_string(),
+                                        ToastStatus::Success,
+                                    ),
+                                );
+                            }
+                            schedule
+                                .lock()
+                                .unwrap()
+                                .refreshed(&workspaces, Instant::now());
+                            if let Some(diff) = replace_if_changed(&workspaces_state, workspaces) {
+                                emit_diff(&app_handle, diff);
+                                SystemTray::new().rebuild_menu(&app_handle);
+                            }
+                        }
+                        Update::Failed(err) => {
+                            if health.failed() {
+                                notify_poll_health(
+                                    &app_handle,
+                                    ShowToastMsg::new(
+                                        "Unable to load workspaces".to_string(),
+                                        format!(
+                                            "Your workspaces might be out of date, DevPod keeps retrying: {}",
+                                            err
+                                        ),
+                                        ToastStatus::Error,
+                                    ),
+                                );
+                            }
+                            if set_refresh_error(&workspaces_state, err) {
+                                SystemTray::new().rebuild_menu(&app_handle);
+                            }
+                        }
+                        Update::Status(key, status) => {
+                            if status == WorkspaceStatus::Running {
+                                // whatever our last operation did, the workspace is back now
+                                let state = app_handle.state::<AppState>();
+                                state.operations.lock().unwrap().forget_finished(&key);
+                            }
+                            let (previous_status, diff) = {
+                                let mut workspaces = workspaces_state.lock().unwrap();
+                                let previous = workspaces.clone();
+                                let diff = workspaces
+                                    .set_status(&key, status)
+                                    .then(|| WorkspacesDiff::between(&previous, &workspaces));
+                                (previous.status(&key), diff)
+                            };
+                            if let Some(diff) = diff {
+                                workspace_notifications::notify_status_changed(
+                                    &app_handle,
+                                    &key,
+                                    previous_status,
+                                    status,
+                                );
+                                emit_diff(&app_handle, diff);
+                                SystemTray::new().rebuild_menu(&app_handle);
                             }
                         }
                     }
@@ -195,3 +1091,877 @@ pub fn setup(app_handle: &AppHandle, state: tauri::State<'_, AppState>) {
         .await;
     });
 }
+
+/// `poll_workspaces` loads the current workspaces of all contexts and hands them to the update thread, returns whether that worked.
+/// If the CLI fails or times out, the `current` state is kept until the next successful poll.
+fn poll_workspaces(
+    runner: &dyn CommandRunner,
+    current: &Mutex<WorkspacesState>,
+    tx: &mpsc::Sender<Update>,
+) -> bool {
+    let current = current.lock().unwrap().clone();
+    let (update, ok) = match WorkspacesState::load_all_with(runner, &current) {
+        Ok(workspaces) => (Update::Workspaces(workspaces), true),
+        Err(err) => {
+            warn!("Failed to load workspaces: {}", err);
+            (Update::Failed(err.to_string()), false)
+        }
+    };
+    if tx.send(update).is_err() {
+        error!("Workspace updates are no longer handled");
+    }
+
+    ok
+}
+
+/// `StatusSchedule` remembers when we last checked the status of each workspace.
+/// Workspaces that are busy, have operations queued or whose status we don't know yet are checked more often than the others.
+/// Workspaces whose last check failed fall back to the idle interval until a check succeeds again.
+#[derive(Debug, Default)]
+struct StatusSchedule {
+    last_checked: HashMap<WorkspaceKey, Instant>,
+    failed: HashSet<WorkspaceKey>,
+}
+
+impl StatusSchedule {
+    /// `due` returns the keys of all workspaces whose status should be checked at `now`.
+    /// `workspaces` are the keys of all workspaces and whether they are active right now.
+    fn due(&mut self, workspaces: &[(WorkspaceKey, bool)], now: Instant) -> Vec<WorkspaceKey> {
+        self.last_checked
+            .retain(|key, _| workspaces.iter().any(|(workspace, _)| workspace == key));
+        self.failed
+            .retain(|key| workspaces.iter().any(|(workspace, _)| workspace == key));
+
+        workspaces
+            .iter()
+            .filter(|(key, active)| {
+                let interval = if *active && !self.failed.contains(key) {
+                    ACTIVE_STATUS_INTERVAL
+                } else {
+                    IDLE_STATUS_INTERVAL
+                };
+
+                match self.last_checked.get(key) {
+                    Some(last_checked) => now.duration_since(*last_checked) >= interval,
+                    None => true,
+                }
+            })
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    fn checked(&mut self, key: &WorkspaceKey, now: Instant) {
+        self.last_checked.insert(key.clone(), now);
+        self.failed.remove(key);
+    }
+
+    fn check_failed(&mut self, key: &WorkspaceKey, now: Instant) {
+        self.last_checked.insert(key.clone(), now);
+        self.failed.insert(key.clone());
+    }
+
+    /// `refreshed` counts the statuses the refresher already brought along with `workspaces` as checked at `now`.
+    fn refreshed(&mut self, workspaces: &WorkspacesState, now: Instant) {
+        for workspace in &workspaces.workspaces {
+            if let (Some(key), Some(..)) = (workspace.key(), workspace.status) {
+                self.checked(&key, now);
+            }
+        }
+    }
+}
+
+/// `poll_statuses` checks the status of all workspaces that are due and hands the results to the update thread.
+/// At most `MAX_CONCURRENT_STATUS_CHECKS` of them run at the same time.
+fn poll_statuses(
+    runner: &dyn CommandRunner,
+    schedule: &Mutex<StatusSchedule>,
+    workspaces: &Mutex<WorkspacesState>,
+    operations: &Mutex<OperationScheduler>,
+    tx: &mpsc::Sender<Update>,
+) {
+    let candidates: Vec<(WorkspaceKey, bool)> = {
+        let workspaces = workspaces.lock().unwrap();
+        let operations = operations.lock().unwrap();
+        workspaces
+            .workspaces
+            .iter()
+            .filter_map(|workspace| {
+                let key = workspace.key()?;
+                let active = matches!(workspace.status, None | Some(WorkspaceStatus::Busy))
+                    || !operations.operations(&key).is_empty();
+
+                Some((key, active))
+            })
+            .collect()
+    };
+
+    let due = schedule.lock().unwrap().due(&candidates, Instant::now());
+    for batch in due.chunks(MAX_CONCURRENT_STATUS_CHECKS) {
+        let results: Vec<_> = thread::scope(|scope| {
+            let checks: Vec<_> = batch
+                .iter()
+                .map(|key| {
+                    scope.spawn(move || {
+                        WorkspaceStatusCommand::new(key.id.clone(), key.context.clone())
+                            .and_then(|cmd| cmd.exec_with(runner))
+                    })
+                })
+                .collect();
+
+            checks.into_iter().map(|check| check.join()).collect()
+        });
+
+        let now = Instant::now();
+        for (key, result) in batch.iter().zip(results) {
+            let mut schedule = schedule.lock().unwrap();
+            match result {
+                Ok(Ok(result)) => {
+                    schedule.checked(key, now);
+                    let status = result.state.unwrap_or(WorkspaceStatus::NotFound);
+                    if tx.send(Update::Status(key.clone(), status)).is_err() {
+                        error!("Workspace updates are no longer handled");
+                    }
+                }
+                Ok(Err(err)) => {
+                    schedule.check_failed(key, now);
+                    warn!("Failed to get status of workspace {}: {}", key, err);
+                }
+                Err(..) => {
+                    schedule.check_failed(key, now);
+                    error!("Status check of workspace {} panicked", key);
+                }
+            }
+        }
+    }
+}
+
+/// `PollHealth` counts consecutive failed polls so we notify the user once per outage.
+#[derive(Debug, Default)]
+struct PollHealth {
+    failures: u32,
+    notified: bool,
+}
+
+impl PollHealth {
+    /// `failed` records a failed poll, returns whether the user should be notified now.
+    fn failed(&mut self) -> bool {
+        self.failures += 1;
+        if self.failures >= FAILURES_BEFORE_NOTIFYING && !self.notified {
+            self.notified = true;
+            return true;
+        }
+
+        false
+    }
+
+    /// `recovered` resets the failures, returns whether the user was notified about them.
+    fn recovered(&mut self) -> bool {
+        let notified = self.notified;
+        *self = Self::default();
+
+        notified
+    }
+}
+
+fn notify_poll_health(app_handle: &AppHandle, toast: ShowToastMsg) {
+    let state = app_handle.state::<AppState>();
+    tauri::async_runtime::block_on(async {
+        if let Err(err) = state.ui_messages.send(UiMessage::ShowToast(toast)).await {
+            error!("Failed to broadcast show toast message: {}", err);
+        }
+    });
+}
+
+/// `set_refresh_error` keeps the current workspaces but marks them as stale, returns whether the error is new.
+fn set_refresh_error(current: &Mutex<WorkspacesState>, err: String) -> bool {
+    let current_workspaces = &mut *current.lock().unwrap();
+    let is_new = current_workspaces.refresh_error.is_none();
+    current_workspaces.refresh_error = Some(err);
+
+    is_new
+}
+
+/// `replace_if_changed` swaps in `workspaces` if they differ from the current state and returns what changed.
+/// The diff is empty if only the refresh error was cleared.
+fn replace_if_changed(
+    current: &Mutex<WorkspacesState>,
+    mut workspaces: WorkspacesState,
+) -> Option<WorkspacesDiff> {
+    let current_workspaces = &mut *current.lock().unwrap();
+    workspaces.merge_statuses(current_workspaces);
+    if current_workspaces != &workspaces {
+        let diff = WorkspacesDiff::between(current_workspaces, &workspaces);
+        *current_workspaces = workspaces;
+        Some(diff)
+    } else {
+        None
+    }
+}
+
+/// `WorkspacesDiff` is the payload of the `workspaces_changed` event.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspacesDiff {
+    added: Vec<Workspace>,
+    /// Keys of the workspaces that are gone
+    removed: Vec<WorkspaceKey>,
+    changed: Vec<WorkspaceChange>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WorkspaceChange {
+    /// The names of the top level fields that differ, as the UI sees them
+    fields: Vec<String>,
+    workspace: Workspace,
+}
+
+impl WorkspacesDiff {
+    fn between(previous: &WorkspacesState, current: &WorkspacesState) -> Self {
+        let mut diff = WorkspacesDiff::default();
+
+        for workspace in &current.workspaces {
+            let previous_workspace = previous
+                .workspaces
+                .iter()
+                .find(|previous_workspace| previous_workspace.is(workspace));
+            match previous_workspace {
+                None => diff.added.push(workspace.clone()),
+                Some(previous_workspace) => {
+                    let fields = changed_fields(previous_workspace, workspace);
+                    if !fields.is_empty() {
+                        diff.changed.push(WorkspaceChange {
+                            fields,
+                            workspace: workspace.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        diff.removed = previous
+            .workspaces
+            .iter()
+            .filter(|previous_workspace| {
+                !current
+                    .workspaces
+                    .iter()
+                    .any(|workspace| workspace.is(previous_workspace))
+            })
+            .filter_map(|previous_workspace| previous_workspace.key())
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// `changed_fields` compares the serialized workspaces so the field names match what the UI receives.
+fn changed_fields(previous: &Workspace, current: &Workspace) -> Vec<String> {
+    let (previous, current) = match (
+        serde_json::to_value(previous),
+        serde_json::to_value(current),
+    ) {
+        (Ok(serde_json::Value::Object(previous)), Ok(serde_json::Value::Object(current))) => {
+            (previous, current)
+        }
+        _ => return vec![],
+    };
+
+    let mut fields: Vec<String> = current
+        .iter()
+        .filter(|(key, value)| previous.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .chain(
+            previous
+                .keys()
+                .filter(|key| !current.contains_key(*key))
+                .cloned(),
+        )
+        .collect();
+    fields.sort();
+
+    fields
+}
+
+fn emit_diff(app_handle: &AppHandle, diff: WorkspacesDiff) {
+    if diff.is_empty() {
+        return;
+    }
+    if let Err(err) = app_handle.emit_all(WORKSPACES_CHANGED_EVENT, diff) {
+        warn!("Failed to emit workspace changes: {}", err);
+    }
+}
+
+/// The workspaces of all contexts as of the last poll, including their status.
+#[tauri::command]
+pub fn get_workspaces(
+    state: tauri::State<'_, AppState>,
+    filter: Option<WorkspaceFilter>,
+) -> Result<WorkspacesState, ()> {
+    let workspaces = state.workspaces.lock().unwrap();
+
+    Ok(match filter {
+        Some(filter) => workspaces.filter(&filter),
+        None => workspaces.clone(),
+    })
+}
+
+#[tauri::command]
+pub fn start_workspace(
+    app_handle: AppHandle,
+    args: UpWorkspaceArgs,
+) -> Result<Operation, DevpodCommandError> {
+    let key = command_key(&app_handle, &args.id, args.context.as_ref());
+    let cmd = UpWorkspaceCommand::new(args)?;
+
+    operations::schedule(&app_handle, &key, OperationKind::Up, move |app_handle| {
+        cmd.stream(app_handle, Caller::Ui)
+    })
+}
+
+#[tauri::command]
+pub fn stop_workspace(
+    app_handle: AppHandle,
+    id: String,
+    context: Option<String>,
+    debug: bool,
+) -> Result<Operation, DevpodCommandError> {
+    let key = command_key(&app_handle, &id, context.as_ref());
+    let cmd = StopWorkspaceCommand::new(id, context, debug)?;
+
+    operations::schedule(&app_handle, &key, OperationKind::Stop, move |app_handle| {
+        cmd.stream(app_handle, Caller::Ui)
+    })
+}
+
+#[tauri::command]
+pub fn delete_workspace(
+    app_handle: AppHandle,
+    args: DeleteWorkspaceArgs,
+) -> Result<Operation, DevpodCommandError> {
+    let key = command_key(&app_handle, &args.id, args.context.as_ref());
+    let cmd = DeleteWorkspaceCommand::new(args)?;
+
+    operations::schedule(
+        &app_handle,
+        &key,
+        OperationKind::Delete,
+        move |app_handle| cmd.stream(app_handle, Caller::Ui),
+    )
+}
+
+#[tauri::command]
+pub fn build_workspace(
+    app_handle: AppHandle,
+    args: BuildWorkspaceArgs,
+) -> Result<CommandHandle, DevpodCommandError> {
+    // builds run in the active context, the source stands in for the ID of the workspace
+    let key = command_key(&app_handle, &args.source, None);
+    let started = Instant::now();
+    let mut handle = BuildWorkspaceCommand::new(args)?.stream(&app_handle, Caller::Ui)?;
+
+    let mut exit = handle.take();
+    tauri::async_runtime::spawn(async move {
+        let succeeded = exit.wait().await == Some(0);
+        workspace_notifications::notify_command_finished(
+            &app_handle,
+            LongRunningCommand::Build,
+            &key,
+            succeeded,
+            started.elapsed(),
+        );
+    });
+
+    Ok(handle)
+}
+
+#[tauri::command]
+pub async fn get_workspace_status(
+    id: String,
+    context: Option<String>,
+) -> Result<WorkspaceStatusResult, DevpodCommandError> {
+    exec_blocking(WorkspaceStatusCommand::new(id, context)?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::runner::FakeRunner;
+
+    const WORKSPACES: &str = r#"[{"id":"a","provider":{"name":"docker"}},{"id":"b"}]"#;
+    const CONTEXTS: &str = r#"[{"name":"default","default":true}]"#;
+
+    /// `respond_list` answers the next poll with `workspaces` in the only context.
+    fn respond_list(runner: FakeRunner, workspaces: &str) -> FakeRunner {
+        runner.respond(0, CONTEXTS, "").respond(0, workspaces, "")
+    }
+
+    fn key(id: &str, context: Option<&str>) -> WorkspaceKey {
+        WorkspaceKey::new(id, context.map(String::from))
+    }
+
+    fn poll_once(runner: &FakeRunner) -> WorkspacesState {
+        let (tx, rx) = mpsc::channel();
+        poll_workspaces(runner, &Mutex::new(WorkspacesState::default()), &tx);
+
+        match rx.try_recv().unwrap() {
+            Update::Workspaces(workspaces) => workspaces,
+            Update::Failed(err) => panic!("unexpected failure: {}", err),
+            Update::Status(..) => panic!("unexpected status"),
+        }
+    }
+
+    #[test]
+    fn should_send_polled_workspaces() {
+        let runner = respond_list(FakeRunner::new(), WORKSPACES);
+
+        let workspaces = poll_once(&runner);
+
+        assert_eq!(workspaces.workspaces.len(), 2);
+        assert_eq!(workspaces.workspaces[0].id(), &Some("a".to_string()));
+    }
+
+    #[test]
+    fn should_list_workspaces_of_all_contexts() {
+        let runner = FakeRunner::new()
+            .respond(
+                0,
+                r#"[{"name":"default","default":true},{"name":"staging"}]"#,
+                "",
+            )
+            .respond(0, WORKSPACES, "")
+            .respond(0, r#"[{"id":"a"}]"#, "");
+
+        let state = WorkspacesState::load_all_with(&runner, &WorkspacesState::default()).unwrap();
+
+        let workspaces: Vec<(Option<&str>, Option<&str>)> = state
+            .workspaces
+            .iter()
+            .map(|workspace| (workspace.id.as_deref(), workspace.context.as_deref()))
+            .collect();
+        assert_eq!(
+            workspaces,
+            vec![
+                (Some("a"), Some("default")),
+                (Some("b"), Some("default")),
+                (Some("a"), Some("staging")),
+            ]
+        );
+        assert_eq!(
+            runner.calls()[2],
+            vec![
+                "devpod-cli",
+                "list",
+                "--output=json",
+                "--context",
+                "staging"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_find_workspaces_by_action_item_id() {
+        let state: WorkspacesState = serde_json::from_str(
+            r#"[{"id":"a","context":"default"},{"id":"a","context":"staging"}]"#,
+        )
+        .unwrap();
+
+        let (action, workspace_id, context) = WorkspacesState::parse_action_item_id(
+            &WorkspacesState::action_item_id(&WorkspaceAction::Stop, "a", Some("staging")),
+        )
+        .unwrap();
+
+        assert_eq!(action, WorkspaceAction::Stop);
+        assert_eq!(state.find(workspace_id, context), state.workspaces.get(1));
+    }
+
+    #[test]
+    fn should_filter_workspaces() {
+        let mut state: WorkspacesState = serde_json::from_str(WORKSPACES).unwrap();
+        state.set_status(&key("b", None), WorkspaceStatus::Running);
+
+        let ids = |filter: WorkspaceFilter| -> Vec<String> {
+            state
+                .filter(&filter)
+                .workspaces
+                .iter()
+                .filter_map(|workspace| workspace.id.clone())
+                .collect()
+        };
+
+        assert_eq!(ids(WorkspaceFilter::default()), vec!["a", "b"]);
+        assert_eq!(
+            ids(WorkspaceFilter {
+                provider: Some("docker".to_string()),
+                ..Default::default()
+            }),
+            vec!["a"]
+        );
+        assert_eq!(
+            ids(WorkspaceFilter {
+                status: Some(WorkspaceStatus::Running),
+                query: Some("B".to_string()),
+                ..Default::default()
+            }),
+            vec!["b"]
+        );
+        assert!(ids(WorkspaceFilter {
+            context: Some("staging".to_string()),
+            ..Default::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn should_only_report_changed_workspaces() {
+        let runner = respond_list(FakeRunner::new(), WORKSPACES);
+        let runner = respond_list(runner, WORKSPACES);
+        let runner = respond_list(runner, r#"[{"id":"a"}]"#);
+        let state = Mutex::new(WorkspacesState::default());
+
+        assert!(replace_if_changed(&state, poll_once(&runner)).is_some());
+        assert!(replace_if_changed(&state, poll_once(&runner)).is_none());
+        assert!(replace_if_changed(&state, poll_once(&runner)).is_some());
+        assert_eq!(state.lock().unwrap().workspaces.len(), 1);
+    }
+
+    #[test]
+    fn should_keep_statuses_between_polls() {
+        let runner = respond_list(respond_list(FakeRunner::new(), WORKSPACES), WORKSPACES);
+        let state = Mutex::new(WorkspacesState::default());
+        replace_if_changed(&state, poll_once(&runner));
+
+        let a = key("a", Some("default"));
+        assert!(state
+            .lock()
+            .unwrap()
+            .set_status(&a, WorkspaceStatus::Running));
+        assert!(!state
+            .lock()
+            .unwrap()
+            .set_status(&a, WorkspaceStatus::Running));
+
+        assert!(replace_if_changed(&state, poll_once(&runner)).is_none());
+        assert_eq!(
+            state.lock().unwrap().workspaces[0].status,
+            Some(WorkspaceStatus::Running)
+        );
+    }
+
+    #[test]
+    fn should_summarize_workspaces() {
+        let mut state: WorkspacesState = serde_json::from_str(WORKSPACES).unwrap();
+        let operations = OperationScheduler::default();
+        assert_eq!(state.summary(&operations).to_string(), "2 stopped");
+
+        state.set_status(&key("a", None), WorkspaceStatus::Running);
+        state.set_status(&key("b", None), WorkspaceStatus::Busy);
+        let summary = state.summary(&operations);
+        assert_eq!(summary.to_string(), "1 running, 1 busy");
+        assert!(!summary.refresh_failed);
+
+        assert_eq!(
+            WorkspacesState::default().summary(&operations).to_string(),
+            "No workspaces"
+        );
+    }
+
+    #[test]
+    fn should_check_active_workspaces_more_often() {
+        let mut schedule = StatusSchedule::default();
+        let (active, idle) = (key("active", None), key("idle", None));
+        let workspaces = vec![(active.clone(), true), (idle.clone(), false)];
+        let start = Instant::now();
+
+        assert_eq!(
+            schedule.due(&workspaces, start),
+            vec![active.clone(), idle.clone()]
+        );
+        schedule.checked(&active, start);
+        schedule.checked(&idle, start);
+
+        assert!(schedule.due(&workspaces, start).is_empty());
+        assert_eq!(
+            schedule.due(&workspaces, start + ACTIVE_STATUS_INTERVAL),
+            vec![active.clone()]
+        );
+        assert_eq!(
+            schedule.due(&workspaces, start + IDLE_STATUS_INTERVAL),
+            vec![active, idle]
+        );
+    }
+
+    #[test]
+    fn should_skip_statuses_the_refresher_brought_along() {
+        let mut state: WorkspacesState = serde_json::from_str(WORKSPACES).unwrap();
+        state.set_status(&key("a", None), WorkspaceStatus::Running);
+        let workspaces = vec![(key("a", None), false), (key("b", None), false)];
+        let mut schedule = StatusSchedule::default();
+        let now = Instant::now();
+
+        schedule.refreshed(&state, now);
+
+        assert_eq!(schedule.due(&workspaces, now), vec![key("b", None)]);
+    }
+
+    #[test]
+    fn should_poll_due_statuses() {
+        let runner = respond_list(FakeRunner::new(), WORKSPACES).respond(
+            0,
+            r#"{"id":"a","state":"Running"}"#,
+            "",
+        );
+        let workspaces = Mutex::new(WorkspacesState::default());
+        replace_if_changed(&workspaces, poll_once(&runner));
+        workspaces
+            .lock()
+            .unwrap()
+            .set_status(&key("b", Some("default")), WorkspaceStatus::Stopped);
+        let operations = Mutex::new(OperationScheduler::default());
+        let schedule = Mutex::new(StatusSchedule::default());
+        schedule
+            .lock()
+            .unwrap()
+            .checked(&key("b", Some("default")), Instant::now());
+        let (tx, rx) = mpsc::channel();
+
+        poll_statuses(&runner, &schedule, &workspaces, &operations, &tx);
+
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(Update::Status(status_key, WorkspaceStatus::Running))
+                if status_key == key("a", Some("default"))
+        ));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(runner.calls().len(), 2);
+    }
+
+    #[test]
+    fn should_back_off_failed_status_checks() {
+        let runner = respond_list(FakeRunner::new(), WORKSPACES)
+            .respond(0, "not json", "")
+            .respond(0, "not json", "");
+        let workspaces = Mutex::new(WorkspacesState::default());
+        replace_if_changed(&workspaces, poll_once(&runner));
+        let operations = Mutex::new(OperationScheduler::default());
+        let schedule = Mutex::new(StatusSchedule::default());
+        let (tx, rx) = mpsc::channel();
+
+        poll_statuses(&runner, &schedule, &workspaces, &operations, &tx);
+
+        assert!(rx.try_recv().is_err());
+        let (a, b) = (key("a", Some("default")), key("b", Some("default")));
+        let candidates = vec![(a.clone(), true), (b.clone(), true)];
+        let now = Instant::now();
+        let mut schedule = schedule.lock().unwrap();
+        assert!(schedule
+            .due(&candidates, now + ACTIVE_STATUS_INTERVAL)
+            .is_empty());
+        assert_eq!(
+            schedule.due(&candidates, now + IDLE_STATUS_INTERVAL),
+            vec![a.clone(), b]
+        );
+
+        schedule.checked(&a, now);
+        assert_eq!(
+            schedule.due(&candidates, now + ACTIVE_STATUS_INTERVAL),
+            vec![a]
+        );
+    }
+
+    #[test]
+    fn should_diff_workspaces() {
+        let previous: WorkspacesState = serde_json::from_str(WORKSPACES).unwrap();
+        let mut current: WorkspacesState =
+            serde_json::from_str(r#"[{"id":"a","provider":{"name":"kubernetes"}},{"id":"c"}]"#)
+                .unwrap();
+        current.set_status(&key("a", None), WorkspaceStatus::Running);
+
+        let diff = WorkspacesDiff::between(&previous, &current);
+
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].id(), &Some("c".to_string()));
+        assert_eq!(diff.removed, vec![key("b", None)]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].fields, vec!["provider", "status"]);
+        assert!(WorkspacesDiff::between(&previous, &previous).is_empty());
+    }
+
+    #[test]
+    fn should_tell_workspaces_with_the_same_id_apart() {
+        let previous: WorkspacesState = serde_json::from_str(
+            r#"[{"id":"a","context":"default"},{"id":"a","context":"staging"}]"#,
+        )
+        .unwrap();
+        let mut current = previous.clone();
+        let staging = key("a", Some("staging"));
+
+        assert!(current.set_status(&staging, WorkspaceStatus::Running));
+        assert_eq!(current.status(&key("a", Some("default"))), None);
+        assert_eq!(current.status(&staging), Some(WorkspaceStatus::Running));
+
+        let diff = WorkspacesDiff::between(&previous, &current);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].workspace.key(), Some(staging.clone()));
+
+        current.workspaces.remove(0);
+        let diff = WorkspacesDiff::between(&previous, &current);
+        assert!(diff.added.is_empty());
+        assert_eq!(diff.removed, vec![key("a", Some("default"))]);
+
+        let mut schedule = StatusSchedule::default();
+        let now = Instant::now();
+        schedule.refreshed(&current, now);
+        let workspaces = vec![(key("a", Some("default")), false), (staging, false)];
+        assert_eq!(
+            schedule.due(&workspaces, now),
+            vec![key("a", Some("default"))]
+        );
+    }
+
+    #[test]
+    fn should_parse_tray_actions() {
+        let actions = [
+            WorkspaceAction::Open(None),
+            WorkspaceAction::Open(Some("vscode".to_string())),
+            WorkspaceAction::Start,
+            WorkspaceAction::Stop,
+            WorkspaceAction::Rebuild,
+            WorkspaceAction::Delete,
+            WorkspaceAction::CopySshCommand,
+            WorkspaceAction::ViewLogs,
+            WorkspaceAction::TogglePin,
+        ];
+        for action in actions {
+            assert_eq!(WorkspaceAction::parse(&action.id()), Some(action));
+        }
+
+        assert_eq!(WorkspaceAction::parse("open_"), None);
+        assert_eq!(WorkspaceAction::parse("unknown"), None);
+    }
+
+    #[test]
+    fn should_only_handle_known_workspaces() {
+        let state: WorkspacesState = serde_json::from_str(WORKSPACES).unwrap();
+
+        assert!(state
+            .on_tray_item_clicked(&WorkspacesState::action_item_id(
+                &WorkspaceAction::Stop,
+                "a",
+                None
+            ))
+            .is_some());
+        assert!(state
+            .on_tray_item_clicked(&WorkspacesState::action_item_id(
+                &WorkspaceAction::Stop,
+                "a",
+                Some("staging")
+            ))
+            .is_none());
+        assert!(state
+            .on_tray_item_clicked(&WorkspacesState::action_item_id(
+                &WorkspaceAction::Stop,
+                "c",
+                None
+            ))
+            .is_none());
+    }
+
+    #[test]
+    fn should_pass_the_context_to_ssh() {
+        assert_eq!(ssh_command("a", None), "devpod ssh a");
+        assert_eq!(
+            ssh_command("a", Some("staging")),
+            "devpod ssh a --context staging"
+        );
+    }
+
+    #[test]
+    fn should_report_failed_polls() {
+        // listing the contexts fails, so does listing the active context
+        let runner = FakeRunner::new().hang().hang().hang().hang().hang().hang();
+        let (tx, rx) = mpsc::channel();
+
+        assert!(!poll_workspaces(
+            &runner,
+            &Mutex::new(WorkspacesState::default()),
+            &tx
+        ));
+
+        assert!(matches!(rx.try_recv(), Ok(Update::Failed(..))));
+    }
+
+    #[test]
+    fn should_keep_last_known_workspaces() {
+        let runner = respond_list(FakeRunner::new(), WORKSPACES);
+        let state = Mutex::new(WorkspacesState::default());
+        replace_if_changed(&state, poll_once(&runner));
+
+        assert!(set_refresh_error(&state, "boom".to_string()));
+        assert!(!set_refresh_error(&state, "boom".to_string()));
+        assert_eq!(state.lock().unwrap().workspaces.len(), 2);
+
+        let runner = respond_list(FakeRunner::new(), WORKSPACES);
+        assert!(replace_if_changed(&state, poll_once(&runner)).is_some());
+        assert_eq!(state.lock().unwrap().refresh_error, None);
+    }
+
+    #[test]
+    fn should_keep_workspaces_of_failing_contexts() {
+        let contexts = r#"[{"name":"default","default":true},{"name":"staging"}]"#;
+        let runner = FakeRunner::new()
+            .respond(0, contexts, "")
+            .respond(0, WORKSPACES, "")
+            .respond(0, r#"[{"id":"a"}]"#, "")
+            .respond(0, contexts, "")
+            .respond(0, r#"[{"id":"a"}]"#, "")
+            .respond(0, "not json", "");
+        let state = Mutex::new(WorkspacesState::default());
+        replace_if_changed(&state, poll_once(&runner));
+
+        let (tx, rx) = mpsc::channel();
+        assert!(poll_workspaces(&runner, &state, &tx));
+        let workspaces = match rx.try_recv().unwrap() {
+            Update::Workspaces(workspaces) => workspaces,
+            _ => panic!("expected workspaces"),
+        };
+
+        let keys: Vec<WorkspaceKey> = workspaces
+            .workspaces
+            .iter()
+            .filter_map(Workspace::key)
+            .collect();
+        assert_eq!(
+            keys,
+            vec![key("a", Some("default")), key("a", Some("staging"))]
+        );
+        assert_eq!(
+            workspaces.context_errors.keys().collect::<Vec<_>>(),
+            vec!["staging"]
+        );
+        assert!(
+            workspaces
+                .summary(&OperationScheduler::default())
+                .refresh_failed
+        );
+    }
+
+    #[test]
+    fn should_notify_once_per_outage() {
+        let mut health = PollHealth::default();
+
+        assert!(!health.recovered());
+        assert!(!health.failed());
+        assert!(!health.failed());
+        assert!(health.failed());
+        assert!(!health.failed());
+        assert!(health.recovered());
+        assert!(!health.failed());
+    }
+}
@@ -34,6 +34,7 @@ type TChannels = {
       }>
     | Readonly<{ type: "ShowDashboard" }>
     | Readonly<{ type: "OpenWorkspaceFailed" }>
+    | Readonly<{ type: "ShowWorkspaceLogs"; workspace_id: string; context: string | null }>
     | Readonly<{
         type: "OpenWorkspace"
         workspace_id: string | null
@@ -10,4 +10,5 @@ export {
   useAllWorkspaceActions,
   useWorkspaceActions,
   startWorkspaceAction,
+  getLatestWorkspaceAction,
 } from "./workspaces"
@@ -1,4 +1,9 @@
 export { usePollWorkspaces } from "./usePollWorkspaces"
-export { useWorkspace, startWorkspaceAction, useWorkspaceActions } from "./useWorkspace"
+export {
+  useWorkspace,
+  startWorkspaceAction,
+  useWorkspaceActions,
+  getLatestWorkspaceAction,
+} from "./useWorkspace"
 export { useWorkspaces } from "./useWorkspaces"
 export { useAllWorkspaceActions } from "./useAllWorkspaceActions"
@@ -68,6 +68,19 @@ export function useWorkspaceActions(
   return data
 }
 
+// The latest action is the active one if there is one, otherwise the last one that finished.
+// Actions are tracked by ID, so they only belong to the workspace in `context` if that's the one we know.
+export function getLatestWorkspaceAction(
+  workspaceID: TWorkspaceID,
+  context: string | null
+): TActionObj | undefined {
+  if ((devPodStore.get(workspaceID)?.context ?? null) !== context) {
+    return undefined
+  }
+
+  return devPodStore.getWorkspaceActions(workspaceID)[0]
+}
+
 export function useWorkspace(workspaceID: TWorkspaceID | undefined): TWorkspaceResult {
   const viewID = useId()
   const data = useSyncExternalStore(
@@ -34,6 +34,27 @@ const initialSettings: TSettings = {
   experimental_multiDevcontainer: false,
   experimental_fleet: true,
   experimental_jupyterNotebooks: true,
+  cliPath: null,
+  environmentProfile: {},
+  workspaceRefreshStrategy: "watch",
+  workspaceRefreshInterval: null,
+  trayGrouping: "none",
+  traySorting: "name",
+  trayFavorites: [],
+  trayRecentLimit: null,
+  workspaceNotifications: {
+    upFinished: true,
+    upFailed: true,
+    buildFinished: true,
+    buildFailed: true,
+    stopped: true,
+    missing: true,
+    idleWarning: true,
+    idleStopped: true,
+  },
+  idleStopTimeout: null,
+  idleStopWarning: null,
+  idleStopExemptions: [],
 }
 function getSettingKeys(): readonly TSetting[] {
   return getKeys(initialSettings)
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface AddProviderArgs {
+  source: string
+  name: string | null
+  options: Record<string, string>
+  useProvider: boolean
+  env: Record<string, string>
+}
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface BuildWorkspaceArgs {
+  source: string
+  providerId: string | null
+  repository: string | null
+  devcontainerPath: string | null
+  platforms: Array<string>
+  skipPush: boolean
+  debug: boolean
+  env: Record<string, string>
+}
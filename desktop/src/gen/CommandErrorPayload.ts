@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommandFailure } from "./CommandFailure"
+
+export interface CommandErrorPayload {
+  kind: string
+  message: string
+  failure: CommandFailure | null
+}
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface CommandFailure {
+  argv: Array<string>
+  exitCode: number | null
+  durationMs: number
+  stderrTail: Array<string>
+  cliMessage: string | null
+}
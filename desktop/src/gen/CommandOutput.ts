@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommandOutputEvent } from "./CommandOutputEvent"
+import type { LogEvent } from "./LogEvent"
+
+export interface CommandOutput {
+  invocationId: string
+  event: CommandOutputEvent
+  log: LogEvent | null
+}
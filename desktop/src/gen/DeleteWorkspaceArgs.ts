@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface DeleteWorkspaceArgs {
+  id: string
+  context: string | null
+  force: boolean
+  debug: boolean
+  env: Record<string, string>
+}
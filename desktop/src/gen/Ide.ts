@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { IdeOption } from "./IdeOption"
+
+export interface Ide {
+  name: string | null
+  displayName: string | null
+  options: Record<string, IdeOption>
+  icon: string | null
+  iconDark: string | null
+  experimental: boolean
+  default: boolean
+}
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface IdeOption {
+  name: string | null
+  description: string | null
+  default: string | null
+  enum: Array<string> | null
+  validationPattern: string | null
+  validationMessage: string | null
+  value: string | null
+}
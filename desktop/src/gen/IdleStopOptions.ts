@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WorkspaceKey } from "./WorkspaceKey"
+
+export interface IdleStopOptions {
+  timeout: number | null
+  warning: number | null
+  exemptions: Array<WorkspaceKey>
+}
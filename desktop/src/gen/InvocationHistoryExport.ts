@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InvocationRecord } from "./InvocationRecord"
+
+export interface InvocationHistoryExport {
+  appVersion: string
+  exportedAt: string
+  invocations: Array<InvocationRecord>
+}
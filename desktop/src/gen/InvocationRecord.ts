@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Caller } from "./Caller"
+
+export interface InvocationRecord {
+  argv: Array<string>
+  caller: Caller
+  startedAt: string
+  durationMs: number
+  exitCode: number | null
+  error: string | null
+}
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LogLevel } from "./LogLevel"
+
+export interface LogEvent {
+  level: LogLevel
+  message: string
+  time: string | null
+  fields: Record<string, unknown>
+}
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MachineProvider } from "./MachineProvider"
+import type { WorkspaceKey } from "./WorkspaceKey"
+
+export interface Machine {
+  id: string
+  folder: string | null
+  provider: MachineProvider | null
+  creationTimestamp: string | null
+  context: string | null
+  workspaces: Array<WorkspaceKey>
+}
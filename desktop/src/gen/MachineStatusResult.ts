@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MachineStatus } from "./MachineStatus"
+
+export interface MachineStatusResult {
+  id: string | null
+  context: string | null
+  provider: string | null
+  state: MachineStatus | null
+}
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationKind } from "./OperationKind"
+import type { OperationStatus } from "./OperationStatus"
+
+export interface Operation {
+  id: string
+  workspaceId: string
+  context: string | null
+  kind: OperationKind
+  status: OperationStatus
+  invocationId: string | null
+}
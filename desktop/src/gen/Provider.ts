@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProviderConfig } from "./ProviderConfig"
+import type { ProviderState } from "./ProviderState"
+
+export interface Provider {
+  config: ProviderConfig | null
+  state: ProviderState | null
+  default: boolean
+}
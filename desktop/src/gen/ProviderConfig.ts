@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProviderOption } from "./ProviderOption"
+import type { ProviderOptionGroup } from "./ProviderOptionGroup"
+import type { ProviderSource } from "./ProviderSource"
+
+export interface ProviderConfig {
+  name: string | null
+  version: string | null
+  source: ProviderSource | null
+  description: string | null
+  optionGroups: Array<ProviderOptionGroup>
+  options: Record<string, ProviderOption>
+  icon: string | null
+  iconDark: string | null
+  home: string | null
+  exec: Record<string, Array<string>> | null
+}
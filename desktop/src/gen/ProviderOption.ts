@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface ProviderOption {
+  value: string | null
+  children: Array<string>
+  description: string | null
+  required: boolean
+  password: boolean
+  type: string | null
+  validationPattern: string | null
+  validationMessage: string | null
+  suggestions: Array<string>
+  enum: Array<string> | null
+  hidden: boolean
+  local: boolean
+  global: boolean
+  default: string | null
+  command: string | null
+  subOptionsCommand: string | null
+}
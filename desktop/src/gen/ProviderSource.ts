@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface ProviderSource {
+  internal: boolean
+  github: string | null
+  file: string | null
+  url: string | null
+  raw: string | null
+}
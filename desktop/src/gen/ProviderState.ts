@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProviderOptionValue } from "./ProviderOptionValue"
+
+export interface ProviderState {
+  initialized: boolean
+  singleMachine: boolean
+  options: Record<string, ProviderOptionValue>
+  creationTimestamp: string | null
+}
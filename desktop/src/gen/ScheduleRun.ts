@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScheduleAction } from "./ScheduleAction"
+
+export interface ScheduleRun {
+  scheduleId: string
+  workspaceId: string
+  action: ScheduleAction
+  ranAt: string
+  error: string | null
+}
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface SetProviderOptionsArgs {
+  id: string
+  options: Record<string, string>
+  singleMachine: boolean
+  reconfigure: boolean
+  dry: boolean
+}
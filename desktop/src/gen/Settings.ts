@@ -1,5 +1,10 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RefreshStrategy } from "./RefreshStrategy"
 import type { SidebarPosition } from "./SidebarPosition"
+import type { TrayGrouping } from "./TrayGrouping"
+import type { TraySorting } from "./TraySorting"
+import type { WorkspaceEvent } from "./WorkspaceEvent"
+import type { WorkspaceKey } from "./WorkspaceKey"
 import type { Zoom } from "./Zoom"
 
 export interface Settings {
@@ -13,4 +18,16 @@ export interface Settings {
   experimental_multiDevcontainer: boolean
   experimental_fleet: boolean
   experimental_jupyterNotebooks: boolean
+  cliPath: string | null
+  environmentProfile: Record<string, string>
+  workspaceRefreshStrategy: RefreshStrategy
+  workspaceRefreshInterval: number | null
+  trayGrouping: TrayGrouping
+  traySorting: TraySorting
+  trayFavorites: Array<WorkspaceKey>
+  trayRecentLimit: number | null
+  workspaceNotifications: Record<WorkspaceEvent, boolean>
+  idleStopTimeout: number | null
+  idleStopWarning: number | null
+  idleStopExemptions: Array<WorkspaceKey>
 }
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TrayGrouping } from "./TrayGrouping"
+import type { TraySorting } from "./TraySorting"
+import type { WorkspaceKey } from "./WorkspaceKey"
+
+export interface TrayOptions {
+  grouping: TrayGrouping
+  sorting: TraySorting
+  favorites: Array<WorkspaceKey>
+  recentLimit: number | null
+}
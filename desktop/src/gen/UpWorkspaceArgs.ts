@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface UpWorkspaceArgs {
+  id: string
+  source: string | null
+  context: string | null
+  ide: string | null
+  providerId: string | null
+  prebuildRepositories: Array<string>
+  devcontainerPath: string | null
+  recreate: boolean
+  debug: boolean
+  env: Record<string, string>
+}
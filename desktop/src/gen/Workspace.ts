@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WorkspaceIDE } from "./WorkspaceIDE"
+import type { WorkspaceMachine } from "./WorkspaceMachine"
+import type { WorkspaceProvider } from "./WorkspaceProvider"
+import type { WorkspaceSource } from "./WorkspaceSource"
+import type { WorkspaceStatus } from "./WorkspaceStatus"
+
+export interface Workspace {
+  id: string | null
+  folder: string | null
+  provider: WorkspaceProvider | null
+  machine: WorkspaceMachine | null
+  ide: WorkspaceIDE | null
+  source: WorkspaceSource | null
+  creationTimestamp: string | null
+  lastUsed: string | null
+  context: string | null
+  status: WorkspaceStatus | null
+}
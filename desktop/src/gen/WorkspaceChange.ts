@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Workspace } from "./Workspace"
+
+export interface WorkspaceChange {
+  fields: Array<string>
+  workspace: Workspace
+}
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WorkspaceStatus } from "./WorkspaceStatus"
+
+export interface WorkspaceFilter {
+  context: string | null
+  provider: string | null
+  status: WorkspaceStatus | null
+  query: string | null
+}
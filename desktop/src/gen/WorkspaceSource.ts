@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export interface WorkspaceSource {
+  gitRepository: string | null
+  gitBranch: string | null
+  gitCommit: string | null
+  localFolder: string | null
+  image: string | null
+}
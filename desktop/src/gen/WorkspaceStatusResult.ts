@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { WorkspaceStatus } from "./WorkspaceStatus"
+
+export interface WorkspaceStatusResult {
+  id: string | null
+  context: string | null
+  provider: string | null
+  state: WorkspaceStatus | null
+}
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Workspace } from "./Workspace"
+import type { WorkspaceChange } from "./WorkspaceChange"
+import type { WorkspaceKey } from "./WorkspaceKey"
+
+export interface WorkspacesDiff {
+  added: Array<Workspace>
+  removed: Array<WorkspaceKey>
+  changed: Array<WorkspaceChange>
+}
@@ -1,7 +1,68 @@
+export * from "./AddProviderArgs"
 export * from "./Asset"
 export * from "./Author"
+export * from "./BuildWorkspaceArgs"
+export * from "./Caller"
+export * from "./CommandErrorPayload"
+export * from "./CommandFailure"
+export * from "./CommandHandle"
+export * from "./CommandOutput"
+export * from "./CommandOutputEvent"
+export * from "./Context"
+export * from "./CreateContextArgs"
+export * from "./CreateMachineArgs"
+export * from "./DeleteWorkspaceArgs"
+export * from "./Ide"
+export * from "./IdeOption"
+export * from "./IdleStopOptions"
+export * from "./InvocationHistoryExport"
+export * from "./InvocationRecord"
+export * from "./LogEvent"
+export * from "./LogLevel"
+export * from "./LogLine"
+export * from "./Machine"
+export * from "./MachineProvider"
+export * from "./MachineStatus"
+export * from "./MachineStatusResult"
+export * from "./Operation"
+export * from "./OperationKind"
+export * from "./OperationStatus"
+export * from "./Provider"
+export * from "./ProviderConfig"
+export * from "./ProviderOption"
+export * from "./ProviderOptionGroup"
+export * from "./ProviderOptionValue"
+export * from "./ProviderSource"
+export * from "./ProviderState"
+export * from "./RefreshStrategy"
 export * from "./Release"
+export * from "./Schedule"
+export * from "./ScheduleAction"
+export * from "./ScheduleArgs"
+export * from "./ScheduleRun"
+export * from "./SetContextOptionsArgs"
+export * from "./SetProviderOptionsArgs"
 export * from "./Settings"
 export * from "./SidebarPosition"
+export * from "./TrayGrouping"
+export * from "./TrayOptions"
+export * from "./TraySorting"
+export * from "./UpWorkspaceArgs"
+export * from "./UseContextArgs"
+export * from "./UseIdeArgs"
+export * from "./UseProviderArgs"
+export * from "./Workspace"
+export * from "./WorkspaceChange"
+export * from "./WorkspaceEvent"
+export * from "./WorkspaceFilter"
+export * from "./WorkspaceIDE"
+export * from "./WorkspaceKey"
+export * from "./WorkspaceMachine"
+export * from "./WorkspaceOperations"
+export * from "./WorkspaceProvider"
+export * from "./WorkspaceSource"
+export * from "./WorkspaceStatus"
+export * from "./WorkspaceStatusResult"
+export * from "./WorkspacesDiff"
 export * from "./Zoom"
 export * from "./index"
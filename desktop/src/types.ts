@@ -124,6 +124,7 @@ export type TWorkspaceID = NonNullable<TWorkspace["id"]>
 export type TWithWorkspaceID = Readonly<{ workspaceID: TWorkspaceID }>
 export type TWorkspace = Readonly<{
   id: string
+  context: TMaybe<string>
   picture: TMaybe<string>
   machine: TMaybe<Readonly<{ machineId: TMaybe<string> }>>
   provider: TMaybe<Readonly<{ name: TMaybe<string> }>>
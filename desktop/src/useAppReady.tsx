@@ -23,7 +23,7 @@ import { useNavigate } from "react-router"
 import { client } from "./client"
 import { ErrorMessageBox } from "./components"
 import { WORKSPACE_SOURCE_BRANCH_DELIMITER, WORKSPACE_SOURCE_COMMIT_DELIMITER } from "./constants"
-import { startWorkspaceAction } from "./contexts"
+import { getLatestWorkspaceAction, startWorkspaceAction } from "./contexts"
 import { Release } from "./gen"
 import { exists, useReleases, useVersion } from "./lib"
 import { Routes } from "./routes"
@@ -155,6 +155,15 @@ export function useAppReady() {
             return
           }
 
+          if (event.type === "ShowWorkspaceLogs") {
+            const latestAction = getLatestWorkspaceAction(event.workspace_id, event.context)
+            navigate(
+              latestAction !== undefined ? Routes.toAction(latestAction.id) : Routes.WORKSPACES
+            )
+
+            return
+          }
+
           if (event.type === "OpenWorkspaceFailed") {
             const message = Object.entries(event)
               .filter(([key]) => key !== "type")